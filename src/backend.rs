@@ -0,0 +1,149 @@
+use std::str::FromStr;
+
+use sea_query::{MysqlQueryBuilder, QueryStatementWriter, SchemaStatementBuilder};
+
+mod mysql;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+/// Database holding the f1db schema to update.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// Configured with the `MYSQL_*` environment variables
+    Mysql,
+    /// Configured with the `PG*` environment variables
+    #[cfg(feature = "postgres")]
+    Postgres,
+    /// Local database file, `SQLITE_DATABASE` or `f1db.db` by default
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl Dialect {
+    /// Renders a statement in the SQL flavor of the database.
+    pub fn build<S: QueryStatementWriter>(self, statement: &S) -> String {
+        match self {
+            Dialect::Mysql => statement.to_string(MysqlQueryBuilder),
+            #[cfg(feature = "postgres")]
+            Dialect::Postgres => statement.to_string(sea_query::PostgresQueryBuilder),
+            #[cfg(feature = "sqlite")]
+            Dialect::Sqlite => statement.to_string(sea_query::SqliteQueryBuilder),
+        }
+    }
+
+    pub fn build_schema<S: SchemaStatementBuilder>(self, statement: &S) -> String {
+        match self {
+            Dialect::Mysql => statement.to_string(MysqlQueryBuilder),
+            #[cfg(feature = "postgres")]
+            Dialect::Postgres => statement.to_string(sea_query::PostgresQueryBuilder),
+            #[cfg(feature = "sqlite")]
+            Dialect::Sqlite => statement.to_string(sea_query::SqliteQueryBuilder),
+        }
+    }
+}
+
+pub trait Backend {
+    fn transaction(&mut self) -> anyhow::Result<Box<dyn Transaction + '_>>;
+}
+
+/// A transaction in which the whole import happens.
+///
+/// Statements are built with sea-query and sent as plain SQL, rows come back
+/// as text whatever the database.
+pub trait Transaction {
+    fn dialect(&self) -> Dialect;
+
+    /// Executes a statement and returns the number of affected rows.
+    fn exec_sql(&mut self, sql: &str) -> anyhow::Result<u64>;
+
+    fn query_sql(&mut self, sql: &str) -> anyhow::Result<Vec<Row>>;
+
+    fn commit(self: Box<Self>) -> anyhow::Result<()>;
+}
+
+impl dyn Transaction + '_ {
+    /// Executes a statement and returns the number of affected rows.
+    pub fn exec<S: QueryStatementWriter>(&mut self, statement: &S) -> anyhow::Result<u64> {
+        let sql = self.dialect().build(statement);
+        self.exec_sql(&sql)
+    }
+
+    pub fn query<S: QueryStatementWriter>(&mut self, statement: &S) -> anyhow::Result<Vec<Row>> {
+        let sql = self.dialect().build(statement);
+        self.query_sql(&sql)
+    }
+
+    pub fn exec_schema<S: SchemaStatementBuilder>(&mut self, statement: &S) -> anyhow::Result<()> {
+        let sql = self.dialect().build_schema(statement);
+        self.exec_sql(&sql)?;
+        Ok(())
+    }
+}
+
+/// A row returned by a query, values are kept in their text representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row(pub Vec<Option<String>>);
+
+impl Row {
+    pub fn get<T>(&self, idx: usize) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get_opt(idx)?
+            .ok_or_else(|| anyhow::anyhow!("unexpected NULL in column {idx}"))
+    }
+
+    pub fn get_opt<T>(&self, idx: usize) -> anyhow::Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        match self.0.get(idx) {
+            None => anyhow::bail!("no column {idx} in row"),
+            Some(None) => Ok(None),
+            Some(Some(value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("invalid value in column {idx} ({value}): {e}")),
+        }
+    }
+}
+
+pub fn connect(dialect: Dialect) -> anyhow::Result<Box<dyn Backend>> {
+    match dialect {
+        Dialect::Mysql => Ok(Box::new(mysql::Mysql::connect()?)),
+        #[cfg(feature = "postgres")]
+        Dialect::Postgres => Ok(Box::new(postgres::Postgres::connect()?)),
+        #[cfg(feature = "sqlite")]
+        Dialect::Sqlite => Ok(Box::new(sqlite::Sqlite::connect()?)),
+    }
+}
+
+/// A sqlite database living as long as the returned backend.
+#[cfg(all(test, feature = "sqlite"))]
+pub fn memory() -> anyhow::Result<Box<dyn Backend>> {
+    Ok(Box::new(sqlite::Sqlite::memory()?))
+}
+
+/// Whether the error comes from a row violating a primary or unique key.
+pub fn is_duplicate(e: &anyhow::Error) -> bool {
+    if let Some(::mysql::Error::MySqlError(e)) = e.downcast_ref() {
+        return e.code == 1062;
+    }
+    #[cfg(feature = "postgres")]
+    if let Some(e) = e.downcast_ref::<::postgres::Error>() {
+        return e.code() == Some(&::postgres::error::SqlState::UNIQUE_VIOLATION);
+    }
+    #[cfg(feature = "sqlite")]
+    if let Some(rusqlite::Error::SqliteFailure(e, _)) = e.downcast_ref() {
+        return matches!(
+            e.extended_code,
+            rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY | rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+        );
+    }
+
+    false
+}
@@ -0,0 +1,81 @@
+use std::env;
+
+use mysql::prelude::*;
+
+use super::{Backend, Dialect, Row, Transaction};
+
+pub struct Mysql(mysql::Conn);
+
+impl Mysql {
+    pub fn connect() -> anyhow::Result<Self> {
+        let user = env::var("MYSQL_USER").unwrap_or("user".into());
+        let password = env::var("MYSQL_PWD").unwrap_or("password".into());
+        let port = env::var("MYSQL_TCP_PORT")
+            .map(|port| port.parse().unwrap_or(3306))
+            .unwrap_or(3306);
+        let db_name = env::var("MYSQL_DATABASE").unwrap_or("f1db".into());
+        let conn = mysql::Conn::new(
+            mysql::OptsBuilder::new()
+                .ip_or_hostname("localhost".into())
+                .tcp_port(port)
+                .user(Some(user))
+                .pass(Some(password))
+                .db_name(Some(db_name))
+                // Count the rows matched by an UPDATE rather than the rows
+                // changed, like the other backends, rollback relies on it
+                .additional_capabilities(mysql::consts::CapabilityFlags::CLIENT_FOUND_ROWS),
+        )?;
+
+        Ok(Self(conn))
+    }
+}
+
+impl Backend for Mysql {
+    fn transaction(&mut self) -> anyhow::Result<Box<dyn Transaction + '_>> {
+        Ok(Box::new(MysqlTransaction(
+            self.0.start_transaction(mysql::TxOpts::default())?,
+        )))
+    }
+}
+
+struct MysqlTransaction<'a>(mysql::Transaction<'a>);
+
+impl Transaction for MysqlTransaction<'_> {
+    fn dialect(&self) -> Dialect {
+        Dialect::Mysql
+    }
+
+    fn exec_sql(&mut self, sql: &str) -> anyhow::Result<u64> {
+        self.0.exec_drop(sql, ())?;
+        Ok(self.0.affected_rows())
+    }
+
+    fn query_sql(&mut self, sql: &str) -> anyhow::Result<Vec<Row>> {
+        let rows = self.0.query::<mysql::Row, _>(sql)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Row(row.unwrap().into_iter().map(text).collect()))
+            .collect())
+    }
+
+    fn commit(self: Box<Self>) -> anyhow::Result<()> {
+        Ok(self.0.commit()?)
+    }
+}
+
+fn text(value: mysql::Value) -> Option<String> {
+    match value {
+        mysql::Value::NULL => None,
+        mysql::Value::Bytes(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        mysql::Value::Int(i) => Some(i.to_string()),
+        mysql::Value::UInt(u) => Some(u.to_string()),
+        mysql::Value::Float(f) => Some(f.to_string()),
+        mysql::Value::Double(d) => Some(d.to_string()),
+        mysql::Value::Date(y, m, d, 0, 0, 0, 0) => Some(format!("{y:04}-{m:02}-{d:02}")),
+        mysql::Value::Date(y, m, d, h, mi, s, _) => {
+            Some(format!("{y:04}-{m:02}-{d:02} {h:02}:{mi:02}:{s:02}"))
+        }
+        mysql::Value::Time(_, _, h, mi, s, _) => Some(format!("{h:02}:{mi:02}:{s:02}")),
+    }
+}
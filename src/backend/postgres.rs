@@ -0,0 +1,70 @@
+use std::env;
+
+use postgres::{NoTls, SimpleQueryMessage};
+
+use super::{Backend, Dialect, Row, Transaction};
+
+pub struct Postgres(postgres::Client);
+
+impl Postgres {
+    pub fn connect() -> anyhow::Result<Self> {
+        let host = env::var("PGHOST").unwrap_or("localhost".into());
+        let user = env::var("PGUSER").unwrap_or("user".into());
+        let password = env::var("PGPASSWORD").unwrap_or("password".into());
+        let port = env::var("PGPORT")
+            .map(|port| port.parse().unwrap_or(5432))
+            .unwrap_or(5432);
+        let db_name = env::var("PGDATABASE").unwrap_or("f1db".into());
+        let client = postgres::Config::new()
+            .host(&host)
+            .port(port)
+            .user(&user)
+            .password(password)
+            .dbname(&db_name)
+            .connect(NoTls)?;
+
+        Ok(Self(client))
+    }
+}
+
+impl Backend for Postgres {
+    fn transaction(&mut self) -> anyhow::Result<Box<dyn Transaction + '_>> {
+        Ok(Box::new(PostgresTransaction(self.0.transaction()?)))
+    }
+}
+
+struct PostgresTransaction<'a>(postgres::Transaction<'a>);
+
+impl Transaction for PostgresTransaction<'_> {
+    fn dialect(&self) -> Dialect {
+        Dialect::Postgres
+    }
+
+    fn exec_sql(&mut self, sql: &str) -> anyhow::Result<u64> {
+        // A failed statement aborts the whole transaction in Postgres, the
+        // savepoint lets callers carry on after a bypassed duplicate.
+        let mut savepoint = self.0.savepoint("statement")?;
+        let affected = savepoint.execute(sql, &[])?;
+        savepoint.commit()?;
+
+        Ok(affected)
+    }
+
+    fn query_sql(&mut self, sql: &str) -> anyhow::Result<Vec<Row>> {
+        Ok(self
+            .0
+            .simple_query(sql)?
+            .into_iter()
+            .filter_map(|message| match message {
+                SimpleQueryMessage::Row(row) => Some(Row((0..row.len())
+                    .map(|i| row.get(i).map(String::from))
+                    .collect())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn commit(self: Box<Self>) -> anyhow::Result<()> {
+        Ok(self.0.commit()?)
+    }
+}
@@ -0,0 +1,72 @@
+use std::env;
+
+use rusqlite::types::ValueRef;
+
+use super::{Backend, Dialect, Row, Transaction};
+
+pub struct Sqlite(rusqlite::Connection);
+
+impl Sqlite {
+    pub fn connect() -> anyhow::Result<Self> {
+        let path = env::var("SQLITE_DATABASE").unwrap_or("f1db.db".into());
+        let conn = rusqlite::Connection::open(path)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        Ok(Self(conn))
+    }
+
+    #[cfg(test)]
+    pub fn memory() -> anyhow::Result<Self> {
+        let conn = rusqlite::Connection::open_in_memory()?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        Ok(Self(conn))
+    }
+}
+
+impl Backend for Sqlite {
+    fn transaction(&mut self) -> anyhow::Result<Box<dyn Transaction + '_>> {
+        Ok(Box::new(SqliteTransaction(self.0.transaction()?)))
+    }
+}
+
+struct SqliteTransaction<'a>(rusqlite::Transaction<'a>);
+
+impl Transaction for SqliteTransaction<'_> {
+    fn dialect(&self) -> Dialect {
+        Dialect::Sqlite
+    }
+
+    fn exec_sql(&mut self, sql: &str) -> anyhow::Result<u64> {
+        Ok(self.0.execute(sql, [])? as u64)
+    }
+
+    fn query_sql(&mut self, sql: &str) -> anyhow::Result<Vec<Row>> {
+        let mut statement = self.0.prepare(sql)?;
+        let columns = statement.column_count();
+
+        let rows = statement.query_map([], |row| {
+            (0..columns)
+                .map(|i| row.get_ref(i).map(text))
+                .collect::<Result<_, _>>()
+                .map(Row)
+        })?;
+
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn commit(self: Box<Self>) -> anyhow::Result<()> {
+        Ok(self.0.commit()?)
+    }
+}
+
+fn text(value: ValueRef) -> Option<String> {
+    match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(i) => Some(i.to_string()),
+        ValueRef::Real(f) => Some(f.to_string()),
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => {
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+    }
+}
@@ -0,0 +1,1189 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+use sea_query::{Alias, Expr, Func, Iden, Order, Query};
+use serde::Serialize;
+
+use crate::backend::Transaction;
+use crate::models::LapFloor;
+use crate::tables::{
+    Circuits, ConstructorStandings, DriverStandings, LapTimes, PitStops, Races, Results,
+    SprintResults,
+};
+
+/// Consistency checks run on the rows of the race before the transaction is
+/// committed, they read the database so the rows of previous runs are checked
+/// too.
+///
+/// The slow laps of the pit stops are only checked when asked, and their
+/// findings stay warnings whatever the strictness.
+pub fn run(
+    race_id: i32,
+    base_path: &Path,
+    strictness: Strictness,
+    slow_laps: bool,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let race = Race::get(race_id, tx)?;
+    let previous_race = race.previous(tx)?;
+
+    for kind in [Kind::Driver, Kind::Constructor] {
+        let standings = kind.standings(race_id, tx)?;
+        if standings.is_empty() {
+            continue;
+        }
+        let previous = match previous_race {
+            Some(previous) => kind.standings(previous, tx)?,
+            None => BTreeMap::new(),
+        };
+        let wins = kind.wins(&race, tx)?;
+        let earned = kind.earned(race_id, tx)?;
+
+        standings_positions(kind, &standings, &mut findings);
+        standings_points(kind, &standings, &previous, &mut findings);
+        standings_wins(kind, &standings, &wins, &mut findings);
+        if previous_race.is_some() && previous.is_empty() {
+            findings.push(Finding::new(
+                Severity::Warning,
+                kind.check("continuity"),
+                "the previous round has no standings, the points gained are not checked".into(),
+            ));
+        } else {
+            standings_continuity(kind, &standings, &previous, &earned, &mut findings);
+        }
+    }
+
+    let laps = laps(race_id, tx)?;
+    if !laps.is_empty() {
+        let results = results(race_id, tx)?;
+        let floor = lap_floor(base_path, &race.circuit_ref)?;
+
+        laps_contiguous(&laps, &mut findings);
+        laps_results(&laps, &results, &mut findings);
+        laps_positions(&laps, &mut findings);
+        laps_plausible(&laps, floor, &mut findings);
+    }
+
+    let stops = pit_stops(race_id, tx)?;
+    let mut advisory = Vec::new();
+    if !stops.is_empty() {
+        let completed = match results(race_id, tx)? {
+            results if !results.is_empty() => results
+                .into_iter()
+                .map(|(id, (completed, _))| (id, completed))
+                .collect(),
+            _ => laps
+                .iter()
+                .map(|(id, laps)| (*id, laps.len() as u32))
+                .collect(),
+        };
+
+        pit_stops_numbers(&stops, &mut findings);
+        pit_stops_laps(&stops, &completed, &mut findings);
+        pit_stops_times(&stops, &mut findings);
+        if slow_laps {
+            pit_stops_slow_laps(&stops, &laps, &mut advisory);
+        }
+    }
+
+    for finding in &mut findings {
+        finding.severity = strictness.apply(finding.severity);
+    }
+    findings.append(&mut advisory);
+
+    Ok(findings)
+}
+
+/// Logs the findings and fails if any of them is an error.
+pub fn report(findings: &[Finding]) -> anyhow::Result<()> {
+    for finding in findings {
+        match finding.severity {
+            Severity::Warning => log::warn!("{finding}"),
+            Severity::Error => log::error!("{finding}"),
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("{errors} check(s) failed, nothing was committed");
+    }
+
+    Ok(())
+}
+
+/// How the findings of the checks are reported.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strictness {
+    /// Every finding is a warning, the import always goes through
+    Lenient,
+    /// Findings keep the severity of their check
+    Normal,
+    /// Every finding is an error, the import is aborted on any of them
+    Strict,
+}
+
+impl Strictness {
+    fn apply(self, severity: Severity) -> Severity {
+        match self {
+            Strictness::Lenient => Severity::Warning,
+            Strictness::Normal => severity,
+            Strictness::Strict => Severity::Error,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Suspicious but possible, e.g. points removed by a penalty.
+    Warning,
+    /// The rows cannot be right, the import is aborted.
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: String,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: String, message: String) -> Self {
+        Finding {
+            severity,
+            check,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.message)
+    }
+}
+
+struct Race {
+    year: i32,
+    round: i32,
+    circuit_ref: String,
+}
+
+impl Race {
+    fn get(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Self> {
+        let row = tx
+            .query(
+                &Query::select()
+                    .columns([(Races::Table, Races::Year), (Races::Table, Races::Round)])
+                    .column((Circuits::Table, Circuits::CircuitRef))
+                    .from(Races::Table)
+                    .inner_join(
+                        Circuits::Table,
+                        Expr::col((Circuits::Table, Circuits::CircuitID))
+                            .equals((Races::Table, Races::CircuitID)),
+                    )
+                    .and_where(Expr::col((Races::Table, Races::RaceID)).eq(race_id))
+                    .to_owned(),
+            )?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("race {race_id} not found"))?;
+
+        Ok(Race {
+            year: row.get(0)?,
+            round: row.get(1)?,
+            circuit_ref: row.get(2)?,
+        })
+    }
+
+    /// Race of the previous round of the season.
+    fn previous(&self, tx: &mut dyn Transaction) -> anyhow::Result<Option<i32>> {
+        tx.query(
+            &Query::select()
+                .column(Races::RaceID)
+                .from(Races::Table)
+                .and_where(Expr::col(Races::Year).eq(self.year))
+                .and_where(Expr::col(Races::Round).lt(self.round))
+                .order_by(Races::Round, Order::Desc)
+                .limit(1)
+                .to_owned(),
+        )?
+        .first()
+        .map(|row| row.get(0))
+        .transpose()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Driver,
+    Constructor,
+}
+
+struct Standing {
+    points: f64,
+    position: u32,
+    position_text: String,
+    wins: u32,
+}
+
+impl Kind {
+    /// Name of a check of the standings table, e.g. `driver_standings.wins`.
+    fn check(self, name: &str) -> String {
+        format!("{}_standings.{name}", self.name())
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Driver => "driver",
+            Kind::Constructor => "constructor",
+        }
+    }
+
+    /// Standings of the race by driver or constructor id.
+    fn standings(
+        self,
+        race_id: i32,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<BTreeMap<i32, Standing>> {
+        let mut query = Query::select();
+        match self {
+            Kind::Driver => query
+                .columns([
+                    DriverStandings::DriverID,
+                    DriverStandings::Points,
+                    DriverStandings::Position,
+                    DriverStandings::PositionText,
+                    DriverStandings::Wins,
+                ])
+                .from(DriverStandings::Table)
+                .and_where(Expr::col(DriverStandings::RaceID).eq(race_id)),
+            Kind::Constructor => query
+                .columns([
+                    ConstructorStandings::ConstructorID,
+                    ConstructorStandings::Points,
+                    ConstructorStandings::Position,
+                    ConstructorStandings::PositionText,
+                    ConstructorStandings::Wins,
+                ])
+                .from(ConstructorStandings::Table)
+                .and_where(Expr::col(ConstructorStandings::RaceID).eq(race_id)),
+        };
+
+        tx.query(&query)?
+            .into_iter()
+            .map(|row| {
+                Ok((
+                    row.get(0)?,
+                    Standing {
+                        points: row.get(1)?,
+                        position: row.get(2)?,
+                        position_text: row.get(3)?,
+                        wins: row.get(4)?,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Races of the season won so far by driver or constructor id.
+    fn wins(self, race: &Race, tx: &mut dyn Transaction) -> anyhow::Result<BTreeMap<i32, u32>> {
+        let id = match self {
+            Kind::Driver => Results::DriverID,
+            Kind::Constructor => Results::ConstructorID,
+        };
+        let id = Alias::new(id.to_string());
+
+        tx.query(
+            &Query::select()
+                .column((Results::Table, id.clone()))
+                .expr(Func::count(Expr::col((Results::Table, Results::RaceID))))
+                .from(Results::Table)
+                .inner_join(
+                    Races::Table,
+                    Expr::col((Races::Table, Races::RaceID))
+                        .equals((Results::Table, Results::RaceID)),
+                )
+                .and_where(Expr::col((Races::Table, Races::Year)).eq(race.year))
+                .and_where(Expr::col((Races::Table, Races::Round)).lte(race.round))
+                .and_where(Expr::col((Results::Table, Results::Position)).eq(1))
+                .group_by_col((Results::Table, id))
+                .to_owned(),
+        )?
+        .into_iter()
+        .map(|row| Ok((row.get(0)?, row.get(1)?)))
+        .collect()
+    }
+
+    /// Points scored in the race and its sprint by driver or constructor id.
+    fn earned(self, race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<BTreeMap<i32, f64>> {
+        let id = match self {
+            Kind::Driver => Results::DriverID,
+            Kind::Constructor => Results::ConstructorID,
+        };
+        let id = Alias::new(id.to_string());
+
+        let mut earned = BTreeMap::new();
+        for table in [Results::Table.to_string(), SprintResults::Table.to_string()] {
+            let table = Alias::new(table);
+            let rows = tx.query(
+                &Query::select()
+                    .column(id.clone())
+                    .expr(Func::sum(Expr::col(Results::Points)))
+                    .from(table)
+                    .and_where(Expr::col(Results::RaceID).eq(race_id))
+                    .group_by_col(id.clone())
+                    .to_owned(),
+            )?;
+            for row in rows {
+                *earned.entry(row.get(0)?).or_default() += row.get::<f64>(1)?;
+            }
+        }
+
+        Ok(earned)
+    }
+}
+
+/// Positions are 1..N and the position text is the position, or `E` for an
+/// excluded entry and `D` for a disqualified one.
+fn standings_positions(
+    kind: Kind,
+    standings: &BTreeMap<i32, Standing>,
+    findings: &mut Vec<Finding>,
+) {
+    let check = kind.check("positions");
+
+    let mut positions = standings
+        .values()
+        .map(|standing| standing.position)
+        .collect::<Vec<_>>();
+    positions.sort();
+    if positions.iter().copied().ne(1..=positions.len() as u32) {
+        findings.push(Finding::new(
+            Severity::Error,
+            check.clone(),
+            format!("positions are not 1 to {}: {positions:?}", positions.len()),
+        ));
+    }
+
+    for (id, standing) in standings {
+        let text = standing.position_text.as_str();
+        let agrees = match text.parse::<u32>() {
+            Ok(position) => position == standing.position,
+            Err(_) => matches!(text, "E" | "D"),
+        };
+        if !agrees {
+            findings.push(Finding::new(
+                Severity::Error,
+                check.clone(),
+                format!(
+                    "{} {id} has position {} but position text {text:?}",
+                    kind.name(),
+                    standing.position
+                ),
+            ));
+        }
+    }
+}
+
+/// Points never go down during a season, unless a penalty removed some.
+fn standings_points(
+    kind: Kind,
+    standings: &BTreeMap<i32, Standing>,
+    previous: &BTreeMap<i32, Standing>,
+    findings: &mut Vec<Finding>,
+) {
+    for (id, standing) in standings {
+        if let Some(before) = previous.get(id) {
+            if standing.points < before.points {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    kind.check("points"),
+                    format!(
+                        "{} {id} went from {} to {} points",
+                        kind.name(),
+                        before.points,
+                        standing.points
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Wins are the races of the season finished in first place.
+fn standings_wins(
+    kind: Kind,
+    standings: &BTreeMap<i32, Standing>,
+    wins: &BTreeMap<i32, u32>,
+    findings: &mut Vec<Finding>,
+) {
+    for (id, standing) in standings {
+        let won = wins.get(id).copied().unwrap_or_default();
+        if standing.wins != won {
+            findings.push(Finding::new(
+                Severity::Error,
+                kind.check("wins"),
+                format!(
+                    "{} {id} has {} wins in the standings but won {won} races",
+                    kind.name(),
+                    standing.wins
+                ),
+            ));
+        }
+    }
+}
+
+/// Standings carry over from the previous round: the points gained are the
+/// points scored in the race and its sprint, and nobody leaves the standings.
+fn standings_continuity(
+    kind: Kind,
+    standings: &BTreeMap<i32, Standing>,
+    previous: &BTreeMap<i32, Standing>,
+    earned: &BTreeMap<i32, f64>,
+    findings: &mut Vec<Finding>,
+) {
+    let check = kind.check("continuity");
+
+    for (id, standing) in standings {
+        let before = previous.get(id).map_or(0.0, |before| before.points);
+        let scored = earned.get(id).copied().unwrap_or_default();
+        if (standing.points - before - scored).abs() > 0.01 {
+            findings.push(Finding::new(
+                Severity::Error,
+                check.clone(),
+                format!(
+                    "{} {id} went from {before} to {} points but scored {scored}",
+                    kind.name(),
+                    standing.points
+                ),
+            ));
+        }
+    }
+
+    for id in previous.keys().filter(|id| !standings.contains_key(id)) {
+        findings.push(Finding::new(
+            Severity::Error,
+            check.clone(),
+            format!(
+                "{} {id} is in the previous standings but not in these ones",
+                kind.name()
+            ),
+        ));
+    }
+}
+
+/// A lap of a driver, time in milliseconds.
+struct Lap {
+    lap: u32,
+    position: Option<u32>,
+    milliseconds: Option<i64>,
+}
+
+/// Laps of the race by driver id, in lap order.
+fn laps(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<BTreeMap<i32, Vec<Lap>>> {
+    let rows = tx.query(
+        &Query::select()
+            .columns([
+                LapTimes::DriverID,
+                LapTimes::Lap,
+                LapTimes::Position,
+                LapTimes::Milliseconds,
+            ])
+            .from(LapTimes::Table)
+            .and_where(Expr::col(LapTimes::RaceID).eq(race_id))
+            .order_by(LapTimes::Lap, Order::Asc)
+            .to_owned(),
+    )?;
+
+    let mut laps = BTreeMap::<i32, Vec<Lap>>::new();
+    for row in rows {
+        laps.entry(row.get(0)?).or_default().push(Lap {
+            lap: row.get(1)?,
+            position: row.get_opt(2)?,
+            milliseconds: row.get_opt(3)?,
+        });
+    }
+
+    Ok(laps)
+}
+
+/// Laps completed and race time in milliseconds by driver id.
+fn results(
+    race_id: i32,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<BTreeMap<i32, (u32, Option<i64>)>> {
+    tx.query(
+        &Query::select()
+            .columns([Results::DriverID, Results::Laps, Results::Milliseconds])
+            .from(Results::Table)
+            .and_where(Expr::col(Results::RaceID).eq(race_id))
+            .to_owned(),
+    )?
+    .into_iter()
+    .map(|row| Ok((row.get(0)?, (row.get(1)?, row.get_opt(2)?))))
+    .collect()
+}
+
+/// Fastest plausible lap of the circuit in milliseconds, from the
+/// `lap_floors.csv` file if present in the csv folder.
+fn lap_floor(base_path: &Path, circuit_ref: &str) -> anyhow::Result<Option<i64>> {
+    let file = base_path.join("lap_floors.csv");
+    if !file.exists() {
+        return Ok(None);
+    }
+
+    let mut rdr = csv::Reader::from_path(file)?;
+    for r in rdr.deserialize::<LapFloor>() {
+        let floor = r?;
+        if floor.circuit_ref == circuit_ref {
+            return Ok(Some(floor.min_lap_time.num_milliseconds()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Laps of each driver go from 1 without gaps.
+fn laps_contiguous(laps: &BTreeMap<i32, Vec<Lap>>, findings: &mut Vec<Finding>) {
+    for (id, laps) in laps {
+        if laps.iter().map(|lap| lap.lap).ne(1..=laps.len() as u32) {
+            findings.push(Finding::new(
+                Severity::Error,
+                "lap_times.contiguous".into(),
+                format!(
+                    "driver {id} laps are not 1 to {}: {:?}",
+                    laps.len(),
+                    laps.iter().map(|lap| lap.lap).collect::<Vec<_>>()
+                ),
+            ));
+        }
+    }
+}
+
+/// Each driver has a lap per lap completed in the results, and the laps add
+/// up to the race time.
+///
+/// The race time is only known for the drivers on the lead lap, it is measured
+/// from the start signal so it is allowed to differ slightly.
+fn laps_results(
+    laps: &BTreeMap<i32, Vec<Lap>>,
+    results: &BTreeMap<i32, (u32, Option<i64>)>,
+    findings: &mut Vec<Finding>,
+) {
+    for (id, (completed, race_time)) in results {
+        let driver_laps = laps.get(id).map_or(&[][..], Vec::as_slice);
+        if driver_laps.len() != *completed as usize {
+            findings.push(Finding::new(
+                Severity::Error,
+                "lap_times.count".into(),
+                format!(
+                    "driver {id} completed {completed} laps but has {} lap times",
+                    driver_laps.len()
+                ),
+            ));
+            continue;
+        }
+
+        if let Some(race_time) = race_time {
+            let total: i64 = driver_laps.iter().filter_map(|lap| lap.milliseconds).sum();
+            if (total - race_time).abs() > race_time / 200 {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "lap_times.total".into(),
+                    format!(
+                        "driver {id} laps add up to {total} ms but the race time is {race_time} ms"
+                    ),
+                ));
+            }
+        }
+    }
+
+    for id in laps
+        .keys()
+        .filter(|id| !results.is_empty() && !results.contains_key(id))
+    {
+        findings.push(Finding::new(
+            Severity::Error,
+            "lap_times.count".into(),
+            format!("driver {id} has lap times but no result"),
+        ));
+    }
+}
+
+/// Positions are unique on each lap.
+fn laps_positions(laps: &BTreeMap<i32, Vec<Lap>>, findings: &mut Vec<Finding>) {
+    let mut positions = BTreeMap::<u32, BTreeSet<u32>>::new();
+    for (id, lap) in laps
+        .iter()
+        .flat_map(|(id, laps)| laps.iter().map(move |lap| (id, lap)))
+    {
+        if let Some(position) = lap.position {
+            if !positions.entry(lap.lap).or_default().insert(position) {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "lap_times.positions".into(),
+                    format!(
+                        "driver {id} shares position {position} on lap {} with another driver",
+                        lap.lap
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Lap times are positive and not faster than the floor of the circuit.
+fn laps_plausible(laps: &BTreeMap<i32, Vec<Lap>>, floor: Option<i64>, findings: &mut Vec<Finding>) {
+    for (id, lap) in laps
+        .iter()
+        .flat_map(|(id, laps)| laps.iter().map(move |lap| (id, lap)))
+    {
+        let message = match (lap.milliseconds, floor) {
+            (Some(milliseconds), _) if milliseconds <= 0 => {
+                format!("driver {id} lap {} took {milliseconds} ms", lap.lap)
+            }
+            (Some(milliseconds), Some(floor)) if milliseconds < floor => format!(
+                "driver {id} lap {} took {milliseconds} ms, faster than the {floor} ms floor of \
+                 the circuit",
+                lap.lap
+            ),
+            _ => continue,
+        };
+        findings.push(Finding::new(
+            Severity::Error,
+            "lap_times.plausible".into(),
+            message,
+        ));
+    }
+}
+
+/// A pit stop of a driver.
+struct Stop {
+    stop: u32,
+    lap: u32,
+    time: String,
+}
+
+/// Pit stops of the race by driver id, in stop order.
+fn pit_stops(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<BTreeMap<i32, Vec<Stop>>> {
+    let rows = tx.query(
+        &Query::select()
+            .columns([
+                PitStops::DriverID,
+                PitStops::Stop,
+                PitStops::Lap,
+                PitStops::Time,
+            ])
+            .from(PitStops::Table)
+            .and_where(Expr::col(PitStops::RaceID).eq(race_id))
+            .order_by(PitStops::Stop, Order::Asc)
+            .to_owned(),
+    )?;
+
+    let mut stops = BTreeMap::<i32, Vec<Stop>>::new();
+    for row in rows {
+        stops.entry(row.get(0)?).or_default().push(Stop {
+            stop: row.get(1)?,
+            lap: row.get(2)?,
+            time: row.get(3)?,
+        });
+    }
+
+    Ok(stops)
+}
+
+/// Stops of each driver go from 1 without gaps.
+fn pit_stops_numbers(stops: &BTreeMap<i32, Vec<Stop>>, findings: &mut Vec<Finding>) {
+    for (id, stops) in stops {
+        if stops
+            .iter()
+            .map(|stop| stop.stop)
+            .ne(1..=stops.len() as u32)
+        {
+            findings.push(Finding::new(
+                Severity::Error,
+                "pit_stops.numbers".into(),
+                format!(
+                    "driver {id} stops are not 1 to {}: {:?}",
+                    stops.len(),
+                    stops.iter().map(|stop| stop.stop).collect::<Vec<_>>()
+                ),
+            ));
+        }
+    }
+}
+
+/// Stops happen on a lap the driver completed.
+fn pit_stops_laps(
+    stops: &BTreeMap<i32, Vec<Stop>>,
+    completed: &BTreeMap<i32, u32>,
+    findings: &mut Vec<Finding>,
+) {
+    for (id, stops) in stops {
+        let Some(completed) = completed.get(id) else {
+            continue;
+        };
+        for stop in stops
+            .iter()
+            .filter(|stop| !(1..=*completed).contains(&stop.lap))
+        {
+            findings.push(Finding::new(
+                Severity::Error,
+                "pit_stops.laps".into(),
+                format!(
+                    "driver {id} stop {} is on lap {} but the driver completed {completed} laps",
+                    stop.stop, stop.lap
+                ),
+            ));
+        }
+    }
+}
+
+/// Stops of each driver happen later in the day as the stop number grows.
+fn pit_stops_times(stops: &BTreeMap<i32, Vec<Stop>>, findings: &mut Vec<Finding>) {
+    for (id, stops) in stops {
+        for pair in stops.windows(2) {
+            let (before, after) = (&pair[0], &pair[1]);
+            let parse = |stop: &Stop| chrono::NaiveTime::parse_from_str(&stop.time, "%H:%M:%S");
+            if let (Ok(before_time), Ok(after_time)) = (parse(before), parse(after)) {
+                if after_time <= before_time {
+                    findings.push(Finding::new(
+                        Severity::Error,
+                        "pit_stops.times".into(),
+                        format!(
+                            "driver {id} stop {} at {} is not after stop {} at {}",
+                            after.stop, after.time, before.stop, before.time
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// The lap of a stop, or the next one for a stop made right after crossing
+/// the line, is slower than the usual laps of the driver.
+fn pit_stops_slow_laps(
+    stops: &BTreeMap<i32, Vec<Stop>>,
+    laps: &BTreeMap<i32, Vec<Lap>>,
+    findings: &mut Vec<Finding>,
+) {
+    for (id, stops) in stops {
+        let Some(laps) = laps.get(id) else {
+            continue;
+        };
+        let mut times = laps
+            .iter()
+            .filter_map(|lap| lap.milliseconds)
+            .collect::<Vec<_>>();
+        if times.is_empty() {
+            continue;
+        }
+        times.sort();
+        let median = times[times.len() / 2];
+
+        for stop in stops {
+            let slow = laps
+                .iter()
+                .filter(|lap| lap.lap == stop.lap || lap.lap == stop.lap + 1)
+                .filter_map(|lap| lap.milliseconds)
+                .any(|milliseconds| milliseconds > median * 105 / 100);
+            if !slow {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "pit_stops.slow_laps".into(),
+                    format!(
+                        "driver {id} stop {} on lap {} shows no slow lap",
+                        stop.stop, stop.lap
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sqlite")]
+    use crate::testing;
+
+    fn standings(rows: &[(i32, f64, u32, &str, u32)]) -> BTreeMap<i32, Standing> {
+        rows.iter()
+            .map(|&(id, points, position, position_text, wins)| {
+                (
+                    id,
+                    Standing {
+                        points,
+                        position,
+                        position_text: position_text.into(),
+                        wins,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Laps of each driver, `(position, milliseconds)` from lap 1.
+    fn laps(rows: &[(i32, &[(u32, i64)])]) -> BTreeMap<i32, Vec<Lap>> {
+        rows.iter()
+            .map(|&(id, laps)| {
+                (
+                    id,
+                    laps.iter()
+                        .zip(1..)
+                        .map(|(&(position, milliseconds), lap)| Lap {
+                            lap,
+                            position: Some(position),
+                            milliseconds: Some(milliseconds),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Stops of a driver, `(stop, lap, time)`.
+    type Stops<'a> = &'a [(u32, u32, &'a str)];
+
+    fn stops(rows: &[(i32, Stops)]) -> BTreeMap<i32, Vec<Stop>> {
+        rows.iter()
+            .map(|&(id, stops)| {
+                (
+                    id,
+                    stops
+                        .iter()
+                        .map(|&(stop, lap, time)| Stop {
+                            stop,
+                            lap,
+                            time: time.into(),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn checks(findings: &[Finding]) -> Vec<(Severity, &str)> {
+        findings
+            .iter()
+            .map(|finding| (finding.severity, finding.check.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn standings_positions_go_from_one() {
+        let mut findings = Vec::new();
+        let ok = standings(&[
+            (1, 25.0, 1, "1", 1),
+            (2, 18.0, 2, "2", 0),
+            (3, 0.0, 3, "D", 0),
+        ]);
+        standings_positions(Kind::Driver, &ok, &mut findings);
+        assert!(findings.is_empty());
+
+        let gap = standings(&[(1, 25.0, 1, "1", 1), (2, 18.0, 3, "3", 0)]);
+        standings_positions(Kind::Driver, &gap, &mut findings);
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Error, "driver_standings.positions")]
+        );
+    }
+
+    #[test]
+    fn standings_position_text_matches_the_position() {
+        let mut findings = Vec::new();
+        let rows = standings(&[(1, 25.0, 1, "2", 1), (9, 18.0, 2, "R", 0)]);
+        standings_positions(Kind::Constructor, &rows, &mut findings);
+        assert_eq!(
+            checks(&findings),
+            [
+                (Severity::Error, "constructor_standings.positions"),
+                (Severity::Error, "constructor_standings.positions")
+            ]
+        );
+    }
+
+    #[test]
+    fn standings_points_going_down_is_a_warning() {
+        let mut findings = Vec::new();
+        let previous = standings(&[(1, 25.0, 1, "1", 1), (2, 18.0, 2, "2", 0)]);
+        let current = standings(&[
+            (1, 43.0, 1, "1", 1),
+            (2, 15.0, 2, "2", 0),
+            (3, 1.0, 3, "3", 0),
+        ]);
+        standings_points(Kind::Driver, &current, &previous, &mut findings);
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Warning, "driver_standings.points")]
+        );
+        assert!(findings[0].message.starts_with("driver 2 "));
+    }
+
+    #[test]
+    fn standings_wins_are_the_races_won() {
+        let mut findings = Vec::new();
+        let rows = standings(&[(1, 50.0, 1, "1", 2), (2, 36.0, 2, "2", 0)]);
+        standings_wins(
+            Kind::Driver,
+            &rows,
+            &BTreeMap::from([(1, 2)]),
+            &mut findings,
+        );
+        assert!(findings.is_empty());
+
+        standings_wins(
+            Kind::Driver,
+            &rows,
+            &BTreeMap::from([(1, 1), (2, 1)]),
+            &mut findings,
+        );
+        assert_eq!(
+            checks(&findings),
+            [
+                (Severity::Error, "driver_standings.wins"),
+                (Severity::Error, "driver_standings.wins")
+            ]
+        );
+    }
+
+    #[test]
+    fn standings_continue_from_the_previous_round() {
+        let previous = standings(&[(1, 25.0, 1, "1", 1), (2, 18.0, 2, "2", 0)]);
+        let earned = BTreeMap::from([(1, 33.0), (2, 25.5), (3, 1.0)]);
+
+        let mut findings = Vec::new();
+        let ok = standings(&[
+            (1, 58.0, 1, "1", 1),
+            (2, 43.5, 2, "2", 0),
+            (3, 1.0, 3, "3", 0),
+        ]);
+        standings_continuity(Kind::Driver, &ok, &previous, &earned, &mut findings);
+        assert!(findings.is_empty());
+
+        let wrong = standings(&[(1, 50.0, 1, "1", 1)]);
+        standings_continuity(Kind::Driver, &wrong, &previous, &earned, &mut findings);
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.message.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "driver 1 went from 25 to 50 points but scored 33",
+                "driver 2 is in the previous standings but not in these ones"
+            ]
+        );
+    }
+
+    #[test]
+    fn laps_go_from_one_without_gaps() {
+        let mut findings = Vec::new();
+        let mut rows = laps(&[(1, &[(1, 90_000), (1, 90_000)])]);
+        laps_contiguous(&rows, &mut findings);
+        assert!(findings.is_empty());
+
+        rows.get_mut(&1).unwrap()[1].lap = 3;
+        laps_contiguous(&rows, &mut findings);
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Error, "lap_times.contiguous")]
+        );
+    }
+
+    #[test]
+    fn laps_match_the_results() {
+        let rows = laps(&[(1, &[(1, 90_000), (1, 90_000)]), (2, &[(2, 91_000)])]);
+
+        let mut findings = Vec::new();
+        let results = BTreeMap::from([(1, (2, Some(180_500))), (2, (1, None))]);
+        laps_results(&rows, &results, &mut findings);
+        assert!(findings.is_empty());
+
+        let results = BTreeMap::from([(1, (2, Some(185_000))), (2, (2, None))]);
+        laps_results(&rows, &results, &mut findings);
+        assert_eq!(
+            checks(&findings),
+            [
+                (Severity::Warning, "lap_times.total"),
+                (Severity::Error, "lap_times.count")
+            ]
+        );
+
+        findings.clear();
+        laps_results(&rows, &BTreeMap::from([(1, (2, None))]), &mut findings);
+        assert_eq!(findings[0].message, "driver 2 has lap times but no result");
+    }
+
+    #[test]
+    fn laps_positions_are_unique() {
+        let mut findings = Vec::new();
+        laps_positions(
+            &laps(&[
+                (1, &[(1, 90_000), (1, 90_000)]),
+                (2, &[(2, 91_000), (1, 89_000)]),
+            ]),
+            &mut findings,
+        );
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Error, "lap_times.positions")]
+        );
+        assert_eq!(
+            findings[0].message,
+            "driver 2 shares position 1 on lap 2 with another driver"
+        );
+    }
+
+    #[test]
+    fn laps_are_positive_and_above_the_floor() {
+        let rows = laps(&[(1, &[(1, 90_000), (1, 0), (1, 70_000)])]);
+
+        let mut findings = Vec::new();
+        laps_plausible(&rows, None, &mut findings);
+        assert_eq!(findings.len(), 1);
+
+        findings.clear();
+        laps_plausible(&rows, Some(75_000), &mut findings);
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.message.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "driver 1 lap 2 took 0 ms",
+                "driver 1 lap 3 took 70000 ms, faster than the 75000 ms floor of the circuit"
+            ]
+        );
+    }
+
+    #[test]
+    fn pit_stops_go_from_one_without_gaps() {
+        let mut findings = Vec::new();
+        pit_stops_numbers(
+            &stops(&[
+                (1, &[(1, 10, "15:20:00"), (2, 30, "15:50:00")]),
+                (2, &[(2, 12, "15:22:00")]),
+            ]),
+            &mut findings,
+        );
+        assert_eq!(checks(&findings), [(Severity::Error, "pit_stops.numbers")]);
+    }
+
+    #[test]
+    fn pit_stops_are_on_completed_laps() {
+        let rows = stops(&[
+            (1, &[(1, 10, "15:20:00"), (2, 58, "16:20:00")]),
+            (2, &[(1, 0, "15:01:00")]),
+            (3, &[(1, 80, "16:40:00")]),
+        ]);
+        let mut findings = Vec::new();
+        pit_stops_laps(&rows, &BTreeMap::from([(1, 57), (2, 57)]), &mut findings);
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.message.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "driver 1 stop 2 is on lap 58 but the driver completed 57 laps",
+                "driver 2 stop 1 is on lap 0 but the driver completed 57 laps"
+            ]
+        );
+    }
+
+    #[test]
+    fn pit_stops_follow_each_other() {
+        let mut findings = Vec::new();
+        pit_stops_times(
+            &stops(&[
+                (1, &[(1, 10, "15:20:00"), (2, 30, "15:20:00")]),
+                (
+                    2,
+                    &[(1, 10, "15:20:00"), (2, 30, "unknown"), (3, 40, "15:10:00")],
+                ),
+            ]),
+            &mut findings,
+        );
+        assert_eq!(checks(&findings), [(Severity::Error, "pit_stops.times")]);
+        assert!(findings[0].message.starts_with("driver 1 stop 2 "));
+    }
+
+    #[test]
+    fn pit_stops_slow_down_their_lap() {
+        let rows = laps(&[(
+            1,
+            &[
+                (1, 90_000),
+                (1, 90_000),
+                (1, 110_000),
+                (1, 90_000),
+                (1, 90_000),
+            ],
+        )]);
+        let mut findings = Vec::new();
+        pit_stops_slow_laps(
+            &stops(&[(1, &[(1, 2, "15:20:00"), (2, 4, "15:25:00")])]),
+            &rows,
+            &mut findings,
+        );
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Warning, "pit_stops.slow_laps")]
+        );
+        assert!(findings[0].message.starts_with("driver 1 stop 2 "));
+    }
+
+    #[test]
+    fn strictness_overrides_the_severity() {
+        for severity in [Severity::Warning, Severity::Error] {
+            assert_eq!(Strictness::Lenient.apply(severity), Severity::Warning);
+            assert_eq!(Strictness::Normal.apply(severity), severity);
+            assert_eq!(Strictness::Strict.apply(severity), Severity::Error);
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn slow_laps_are_checked_on_demand_and_never_fail() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+        let mut tx = db.transaction().unwrap();
+        testing::import(&files, tx.as_mut()).unwrap();
+        // Verstappen's laps are all the same, the stop shows no slow lap
+        tx.exec_sql(
+            "INSERT INTO pitStops (raceId, driverId, stop, lap, time, duration, milliseconds) \
+             VALUES (2, 830, 1, 2, '15:03:00', '2.345', 2345)",
+        )
+        .unwrap();
+
+        let findings = run(
+            testing::RACE,
+            &files.0,
+            Strictness::Strict,
+            false,
+            tx.as_mut(),
+        )
+        .unwrap();
+        assert!(findings.is_empty());
+
+        let findings = run(
+            testing::RACE,
+            &files.0,
+            Strictness::Strict,
+            true,
+            tx.as_mut(),
+        )
+        .unwrap();
+        assert_eq!(
+            checks(&findings),
+            [(Severity::Warning, "pit_stops.slow_laps")]
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use clap::{
+    builder::PossibleValuesParser, error::ErrorKind, Args, CommandFactory, Parser, Subcommand,
+};
+
+use crate::backend::Dialect;
+use crate::checks::Strictness;
+use crate::importers::{Table, TABLES};
+use crate::input::{Format, Input};
+use crate::journal::Target;
+use crate::logging::LogFormat;
+
+/// Updates the f1db database with the data of a race weekend.
+#[derive(Parser, Debug)]
+#[command(version, subcommand_negates_reqs = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub import: ImportArgs,
+
+    /// Database to update
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "F1_SQL_UPDATER_BACKEND",
+        default_value = "mysql"
+    )]
+    pub backend: Dialect,
+
+    /// Log more, `-vv` logs every row
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Log less, `-qq` only logs errors
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        conflicts_with = "verbose"
+    )]
+    pub quiet: u8,
+
+    /// Format of the logs
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "F1_SQL_UPDATER_LOG_FORMAT",
+        default_value = "text"
+    )]
+    pub log_format: LogFormat,
+}
+
+impl Cli {
+    /// Parses the command line, global options go before or after the command.
+    ///
+    /// The round to import cannot be given with a command, it would be
+    /// silently ignored.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let cli = Self::try_parse_from(args)?;
+        if cli.command.is_some() && cli.import.round.is_some() {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                "a round to import cannot be given with a command",
+            ));
+        }
+
+        Ok(cli)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create the missing f1db and updater tables, at the latest migration
+    InitSchema,
+    /// Apply the pending schema migrations, or revert down to a version
+    Migrate {
+        /// Version to migrate to, the latest by default
+        #[arg(long)]
+        to: Option<u32>,
+    },
+    /// Revert the rows written by a previous import
+    Rollback(RollbackArgs),
+    /// Compare the input files of a round with the rows in the database
+    Diff(DiffArgs),
+    /// Import every round of a season, from `<dir>/<year>/<round>/`
+    ImportSeason(SeasonArgs),
+    /// Write the input files of a round from the rows in the database
+    Export {
+        #[arg(long)]
+        year: i32,
+        #[arg(long)]
+        round: u16,
+        /// Folder the files are written to
+        #[arg(long, default_value = "export")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct SeasonArgs {
+    #[arg(long)]
+    pub year: i32,
+
+    /// Folder holding a subfolder per season
+    #[arg(long, default_value = "csv")]
+    pub dir: PathBuf,
+
+    /// Import the whole season in one transaction instead of one per round
+    #[arg(long)]
+    pub single_transaction: bool,
+
+    /// Skip the rounds before this one, to resume a failed import
+    #[arg(long)]
+    pub from_round: Option<u16>,
+
+    #[command(flatten)]
+    pub options: ImportOptions,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    #[command(flatten)]
+    pub import: ImportArgs,
+
+    #[arg(long, value_enum, default_value = "text")]
+    pub output: Output,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct RollbackArgs {
+    /// Id of the run in `updaterRuns`
+    #[arg(long)]
+    pub run: Option<i32>,
+
+    /// Latest run of a round, as `<year>/<round>`
+    #[arg(long, value_parser = parse_race)]
+    pub race: Option<(i32, u16)>,
+}
+
+impl From<RollbackArgs> for Target {
+    fn from(args: RollbackArgs) -> Self {
+        match (args.run, args.race) {
+            (Some(run_id), _) => Target::Run(run_id),
+            (None, Some((year, round))) => Target::Race { year, round },
+            (None, None) => unreachable!("enforced by the argument group"),
+        }
+    }
+}
+
+fn parse_race(value: &str) -> Result<(i32, u16), String> {
+    let (year, round) = value
+        .split_once('/')
+        .ok_or_else(|| format!("expected <year>/<round>, got {value}"))?;
+
+    Ok((
+        year.parse().map_err(|e| format!("invalid year: {e}"))?,
+        round.parse().map_err(|e| format!("invalid round: {e}"))?,
+    ))
+}
+
+/// Imports a round when no command is given.
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Round number of the race in the current season
+    #[arg(required = true)]
+    pub round: Option<u16>,
+
+    /// Whether the round is a sprint weekend, detected from the race and the
+    /// input files by default
+    #[arg(action = clap::ArgAction::Set)]
+    pub is_sprint: Option<bool>,
+
+    #[command(flatten)]
+    pub options: ImportOptions,
+}
+
+/// Options shared by the import of a round and of a season.
+#[derive(Args, Debug)]
+pub struct ImportOptions {
+    /// Format of the input files, guessed from their extension by default
+    #[arg(long, value_enum, env = "F1_SQL_UPDATER_INPUT_FORMAT")]
+    pub format: Option<Format>,
+
+    /// Only import these tables
+    #[arg(long, value_delimiter = ',', value_parser = table_names(), conflicts_with = "skip")]
+    pub only: Vec<String>,
+
+    /// Do not import these tables
+    #[arg(long, value_delimiter = ',', value_parser = table_names())]
+    pub skip: Vec<String>,
+
+    /// Import even if the database schema differs from the expected one
+    #[arg(long)]
+    pub no_schema_check: bool,
+
+    /// Commit even if the imported rows fail the consistency checks
+    #[arg(long)]
+    pub no_checks: bool,
+
+    /// Severity of the consistency check findings
+    #[arg(
+        long,
+        value_enum,
+        default_value = "normal",
+        conflicts_with = "no_checks"
+    )]
+    pub strictness: Strictness,
+
+    /// Warn about the pit stops whose lap is not slower than usual, a rough
+    /// check that is never an error
+    #[arg(long, conflicts_with = "no_checks")]
+    pub check_slow_laps: bool,
+
+    /// Write a JSON summary of the import to this file, even if it fails
+    #[arg(long, env = "F1_SQL_UPDATER_REPORT")]
+    pub report: Option<PathBuf>,
+}
+
+impl ImportOptions {
+    /// Whether the table was selected with `--only` and `--skip`.
+    pub fn selects(&self, table: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|t| t == table))
+            && !self.skip.iter().any(|t| t == table)
+    }
+
+    /// The [`TABLES`] to import for the round, in order.
+    pub fn tables<'a>(
+        &'a self,
+        input: &'a Input,
+        is_sprint: bool,
+    ) -> impl Iterator<Item = &'static Table> + 'a {
+        TABLES.iter().filter(move |table| {
+            if table.sprint && !is_sprint {
+                return false;
+            }
+            if !self.selects(table.name) {
+                log::info!("skipping {}", table.name);
+                return false;
+            }
+            if table.optional && !input.exists(table.file) {
+                log::warn!("{} not found, skipping {}", table.file, table.name);
+                return false;
+            }
+
+            true
+        })
+    }
+}
+
+fn table_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(TABLES.iter().map(|t| t.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Cli, clap::Error> {
+        Cli::try_parse_args(std::iter::once("f1-sql-updater").chain(args.split_whitespace()))
+    }
+
+    #[test]
+    fn imports_a_round_without_command() {
+        let cli = parse("5 true --only results").unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.import.round, Some(5));
+        assert_eq!(cli.import.is_sprint, Some(true));
+        assert_eq!(cli.import.options.only, ["results"]);
+
+        assert_eq!(parse("5").unwrap().import.is_sprint, None);
+        assert!(parse("").is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn global_options_go_before_the_command() {
+        let cli = parse("--backend sqlite init-schema").unwrap();
+        assert!(matches!(cli.command, Some(Command::InitSchema)));
+        assert_eq!(cli.backend, Dialect::Sqlite);
+
+        let cli = parse("-q diff 2").unwrap();
+        assert!(matches!(cli.command, Some(Command::Diff(_))));
+        assert_eq!(cli.quiet, 1);
+
+        let cli = parse("-q export --year 2026 --round 5 --backend sqlite").unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Export { round: 5, .. })
+        ));
+        assert_eq!(cli.backend, Dialect::Sqlite);
+    }
+
+    #[test]
+    fn refuses_a_round_with_a_command() {
+        let e = parse("5 init-schema").err().unwrap();
+        assert_eq!(e.kind(), ErrorKind::ArgumentConflict);
+        assert!(parse("migrate 5").is_err());
+    }
+
+    #[test]
+    fn rollback_takes_a_run_or_a_race() {
+        let cli = parse("rollback --race 2026/5").unwrap();
+        let Some(Command::Rollback(args)) = cli.command else {
+            panic!("not a rollback: {cli:?}");
+        };
+        assert!(matches!(
+            args.into(),
+            Target::Race {
+                year: 2026,
+                round: 5
+            }
+        ));
+
+        assert!(parse("rollback").is_err());
+        assert!(parse("rollback --run 1 --race 2026/5").is_err());
+        assert!(parse("rollback --race 2026").is_err());
+    }
+}
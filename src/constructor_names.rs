@@ -0,0 +1,284 @@
+use std::collections::{BTreeSet, HashMap};
+
+use sea_query::Query;
+use serde::Deserialize;
+
+use crate::backend::Transaction;
+use crate::input::Input;
+use crate::resolver::season_entries;
+use crate::tables::Constructors;
+
+/// Words carrying no information about the constructor in entrant names.
+const NOISE: &[&str] = &["f1", "formula", "one", "1", "team", "racing", "scuderia"];
+
+/// Minimal Jaro-Winkler similarity for the last resort fuzzy match.
+const FUZZY_THRESHOLD: f64 = 0.9;
+
+/// Maps official entrant names ("Oracle Red Bull Racing", "MoneyGram Haas F1
+/// Team", ...) to f1db constructor ids.
+///
+/// Entrants are matched, in order, against:
+/// 1. the `constructor_aliases` file, if present with the input files
+/// 2. the exact constructor name
+/// 3. the constructor names once normalised, sponsors are dropped by looking
+///    for the constructor words inside the entrant words
+/// 4. the closest constructor name, if similar enough
+///
+/// Constructors entered during the season are preferred. When several of them
+/// match equally well a warning listing the candidates is logged.
+pub struct ConstructorNames {
+    aliases: HashMap<String, i32>,
+    constructors: Vec<Constructor>,
+}
+
+struct Constructor {
+    id: i32,
+    name: String,
+    words: Vec<String>,
+    in_season: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct Alias {
+    entrant: String,
+    constructor_id: Option<i32>,
+    constructor_ref: Option<String>,
+}
+
+impl ConstructorNames {
+    pub fn load(year: i32, input: &Input, tx: &mut dyn Transaction) -> anyhow::Result<Self> {
+        let season = tx
+            .query(&season_entries(year, &["constructorId"]))?
+            .iter()
+            .map(|row| row.get(0))
+            .collect::<Result<BTreeSet<i32>, _>>()?;
+
+        let mut by_ref = HashMap::new();
+        let mut constructors = Vec::new();
+        let query = Query::select()
+            .columns([
+                Constructors::ConstructorID,
+                Constructors::ConstructorRef,
+                Constructors::Name,
+            ])
+            .from(Constructors::Table)
+            .to_owned();
+        for row in tx.query(&query)? {
+            let id = row.get(0)?;
+            let constructor_ref: String = row.get(1)?;
+            let name: String = row.get(2)?;
+            by_ref.insert(constructor_ref, id);
+            constructors.push(Constructor {
+                id,
+                words: words(&name),
+                name,
+                in_season: season.contains(&id),
+            });
+        }
+
+        let mut aliases = HashMap::new();
+        if input.exists("constructor_aliases") {
+            for alias in input.read::<Alias>("constructor_aliases")? {
+                let id = match (alias.constructor_id, &alias.constructor_ref) {
+                    (Some(id), _) => id,
+                    (_, Some(constructor_ref)) => {
+                        *by_ref.get(constructor_ref).ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "unknown constructor_ref in aliases ({constructor_ref})"
+                            )
+                        })?
+                    }
+                    _ => anyhow::bail!("alias without constructor ({})", alias.entrant),
+                };
+                aliases.insert(words(&alias.entrant).join(" "), id);
+            }
+        }
+
+        Ok(Self {
+            aliases,
+            constructors,
+        })
+    }
+
+    pub fn constructor_id(&self, entrant: &str) -> anyhow::Result<i32> {
+        let entrant_words = words(entrant);
+        if let Some(id) = self.aliases.get(&entrant_words.join(" ")) {
+            return Ok(*id);
+        }
+
+        let exact = self
+            .constructors
+            .iter()
+            .filter(|c| c.name == entrant)
+            .collect::<Vec<_>>();
+        if let Some(id) = self.pick(entrant, &exact) {
+            return Ok(id);
+        }
+
+        // The longest constructor name found inside the entrant name wins
+        let mut contained = self
+            .constructors
+            .iter()
+            .filter(|c| !c.words.is_empty())
+            .filter(|c| {
+                entrant_words
+                    .windows(c.words.len())
+                    .any(|w| w == c.words.as_slice())
+            })
+            .collect::<Vec<_>>();
+        let longest = contained.iter().map(|c| c.words.join(" ").len()).max();
+        contained.retain(|c| Some(c.words.join(" ").len()) == longest);
+        if let Some(id) = self.pick(entrant, &contained) {
+            return Ok(id);
+        }
+
+        let entrant_words = entrant_words.join(" ");
+        let mut scored = self
+            .constructors
+            .iter()
+            .map(|c| (strsim::jaro_winkler(&entrant_words, &c.words.join(" ")), c))
+            .filter(|(score, _)| *score >= FUZZY_THRESHOLD)
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let best = scored.first().map(|(score, _)| *score);
+        let closest = scored
+            .into_iter()
+            .filter(|(score, _)| Some(*score) == best)
+            .map(|(_, c)| c)
+            .collect::<Vec<_>>();
+        if let Some(id) = self.pick(entrant, &closest) {
+            log::warn!("entrant {entrant} fuzzily matched to constructor {id}");
+            return Ok(id);
+        }
+
+        anyhow::bail!(
+            "no constructor found for entrant ({entrant}), add it to the constructor_aliases file"
+        )
+    }
+
+    /// Picks among equally good matches, preferring the constructors of the
+    /// season and warning when it is still a coin toss.
+    fn pick(&self, entrant: &str, matches: &[&Constructor]) -> Option<i32> {
+        let season = matches.iter().filter(|c| c.in_season).collect::<Vec<_>>();
+        let candidates = if season.is_empty() {
+            matches.iter().collect()
+        } else {
+            season
+        };
+
+        if candidates.len() > 1 {
+            log::warn!(
+                "entrant {entrant} is ambiguous, candidates: {:?}",
+                candidates
+                    .iter()
+                    .map(|c| format!("{} ({})", c.name, c.id))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        candidates.first().map(|c| c.id)
+    }
+}
+
+fn words(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !NOISE.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sqlite")]
+    use crate::{input::Format, testing};
+
+    /// Names of the 2024 grid and a few constructors of the past.
+    fn names() -> ConstructorNames {
+        let constructors = [
+            (1, "McLaren", true),
+            (3, "Williams", true),
+            (6, "Ferrari", true),
+            (9, "Red Bull", true),
+            (131, "Mercedes", true),
+            (210, "Haas F1 Team", true),
+            (214, "Alpine F1 Team", true),
+            (215, "RB F1 Team", true),
+            (117, "Aston Martin", true),
+            (15, "Sauber", true),
+            (58, "Alpine", false),
+            (4, "Renault", false),
+            (5, "Toro Rosso", false),
+        ];
+
+        ConstructorNames {
+            aliases: HashMap::from([(words("Stake F1 Team Kick Sauber").join(" "), 15)]),
+            constructors: constructors
+                .into_iter()
+                .map(|(id, name, in_season)| Constructor {
+                    id,
+                    name: name.into(),
+                    words: words(name),
+                    in_season,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn sponsors_are_dropped() {
+        let names = names();
+        for (entrant, id) in [
+            ("McLaren", 1),
+            ("Oracle Red Bull Racing", 9),
+            ("McLaren Formula 1 Team", 1),
+            ("Scuderia Ferrari HP", 6),
+            ("Mercedes-AMG PETRONAS F1 Team", 131),
+            ("MoneyGram Haas F1 Team", 210),
+            ("Aston Martin Aramco F1 Team", 117),
+            ("Williams Racing", 3),
+            ("Visa Cash App RB F1 Team", 215),
+        ] {
+            assert_eq!(names.constructor_id(entrant).unwrap(), id, "{entrant}");
+        }
+    }
+
+    #[test]
+    fn aliases_come_first() {
+        assert_eq!(
+            names().constructor_id("Stake F1 Team Kick Sauber").unwrap(),
+            15
+        );
+    }
+
+    #[test]
+    fn season_constructors_are_preferred() {
+        // Both Alpine and Alpine F1 Team are named in the entrant
+        assert_eq!(names().constructor_id("BWT Alpine F1 Team").unwrap(), 214);
+    }
+
+    #[test]
+    fn close_names_match_fuzzily() {
+        let names = names();
+        assert_eq!(names.constructor_id("Ferarri").unwrap(), 6);
+        assert!(names.constructor_id("Andretti Cadillac").is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn aliases_are_read_in_the_input_format() {
+        let mut db = testing::database();
+        let mut tx = db.transaction().unwrap();
+        let folder = testing::folder();
+        std::fs::write(
+            folder.0.join("constructor_aliases.json"),
+            r#"[{"entrant": "Papaya Racing", "constructor_ref": "mclaren"}]"#,
+        )
+        .unwrap();
+
+        let input = Input::new(&folder.0, Some(Format::Json));
+        let names = ConstructorNames::load(2026, &input, tx.as_mut()).unwrap();
+        assert_eq!(names.constructor_id("Papaya Racing").unwrap(), 1);
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use sea_query::{value::sea_value_to_json_value, Alias, Expr, Iden, Query, SimpleExpr};
+use serde::Serialize;
+use serde_json::Value as Json;
+
+use crate::backend::Transaction;
+use crate::export;
+use crate::importers::Importer;
+use crate::input::Input;
+use crate::models;
+use crate::resolver::Resolver;
+use crate::tables::ConstructorResults;
+
+/// Column of the race in every f1db table written by the importers.
+const RACE_ID: &str = "raceId";
+
+/// Values of a row by column, as text.
+pub type Fields = BTreeMap<String, Option<String>>;
+
+/// Differences between an input file and the rows of its table for the race.
+///
+/// Rows are matched on the [`Importer::key`] columns.
+#[derive(Serialize, Debug)]
+pub struct TableDiff {
+    pub table: &'static str,
+    /// Rows of the file missing from the database.
+    pub added: Vec<Fields>,
+    /// Rows of the database missing from the file.
+    pub removed: Vec<Fields>,
+    pub changed: Vec<Change>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Change {
+    pub key: Fields,
+    /// Database and file values of the columns that differ.
+    pub columns: BTreeMap<String, [Option<String>; 2]>,
+}
+
+/// Compares the records of `I::FILE` with the rows of `I::TABLE`.
+pub fn rows<I: Importer>(
+    race_id: i32,
+    input: &Input,
+    resolver: &mut Resolver,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<TableDiff> {
+    let file = file_rows::<I>(race_id, input, resolver, tx)?;
+    let database = database_rows::<I>(race_id, tx)?;
+
+    Ok(compare(I::FILE, file, database))
+}
+
+/// Compares the `constructor_results` file with `constructorResults`.
+///
+/// On sprint weekends the import merges the sprint points into the race
+/// points, they are taken out again as the file only holds the race points.
+pub fn constructor_results(
+    race_id: i32,
+    input: &Input,
+    resolver: &mut Resolver,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<TableDiff> {
+    type I = models::ConstructorResult;
+
+    let file = file_rows::<I>(race_id, input, resolver, tx)?;
+    let mut database = database_rows::<I>(race_id, tx)?;
+    let sprint = export::sprint_points(race_id, tx)?;
+
+    let constructor_id = ConstructorResults::ConstructorID.to_string();
+    let points = ConstructorResults::Points.to_string();
+    for fields in database.values_mut() {
+        let Some(sprint) = fields[&constructor_id]
+            .as_deref()
+            .and_then(|id| sprint.get(&id.parse().ok()?))
+        else {
+            continue;
+        };
+        if let Some(Some(value)) = fields.get_mut(&points) {
+            *value = (value.parse::<f64>()? - f64::from(*sprint)).to_string();
+        }
+    }
+
+    Ok(compare(I::FILE, file, database))
+}
+
+/// Rows of the file by key, as they would be inserted.
+fn file_rows<I: Importer>(
+    race_id: i32,
+    input: &Input,
+    resolver: &mut Resolver,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<BTreeMap<Fields, Fields>> {
+    // Every row has the same race, the column is left out
+    let columns = columns::<I>();
+
+    let mut file = BTreeMap::new();
+    for record in input.read::<I>(I::FILE)? {
+        let fields = columns
+            .iter()
+            .cloned()
+            .zip(record.values(race_id, resolver, tx)?.iter().map(text))
+            .filter(|(column, _)| column != RACE_ID)
+            .map(|(column, value)| Ok((column, value?)))
+            .collect::<anyhow::Result<Fields>>()?;
+        file.insert(key::<I>(&fields), fields);
+    }
+
+    Ok(file)
+}
+
+/// Rows of the race in the table by key.
+fn database_rows<I: Importer>(
+    race_id: i32,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<BTreeMap<Fields, Fields>> {
+    let columns = columns::<I>();
+
+    let mut database = BTreeMap::new();
+    let existing = tx.query(
+        &Query::select()
+            .columns(I::columns())
+            .from(I::TABLE)
+            .and_where(Expr::col(Alias::new(RACE_ID)).eq(race_id))
+            .to_owned(),
+    )?;
+    for row in existing {
+        let fields: Fields = columns
+            .iter()
+            .cloned()
+            .zip(row.0)
+            .filter(|(column, _)| column != RACE_ID)
+            .collect();
+        database.insert(key::<I>(&fields), fields);
+    }
+
+    Ok(database)
+}
+
+fn columns<I: Importer>() -> Vec<String> {
+    I::columns().iter().map(|c| c.to_string()).collect()
+}
+
+fn key<I: Importer>(fields: &Fields) -> Fields {
+    I::key()
+        .iter()
+        .map(|c| c.to_string())
+        .filter(|c| c != RACE_ID)
+        .map(|c| {
+            let value = fields.get(&c).cloned().flatten();
+            (c, value)
+        })
+        .collect()
+}
+
+fn compare(
+    table: &'static str,
+    file: BTreeMap<Fields, Fields>,
+    database: BTreeMap<Fields, Fields>,
+) -> TableDiff {
+    let mut diff = TableDiff {
+        table,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (key, fields) in &file {
+        let Some(existing) = database.get(key) else {
+            diff.added.push(fields.clone());
+            continue;
+        };
+
+        let columns: BTreeMap<_, _> = fields
+            .iter()
+            .filter(|(column, value)| !same(&existing[*column], value))
+            .map(|(column, value)| (column.clone(), [existing[column].clone(), value.clone()]))
+            .collect();
+        if !columns.is_empty() {
+            diff.changed.push(Change {
+                key: key.clone(),
+                columns,
+            });
+        }
+    }
+    diff.removed = database
+        .into_iter()
+        .filter(|(key, _)| !file.contains_key(key))
+        .map(|(_, fields)| fields)
+        .collect();
+
+    diff
+}
+
+fn text(value: &SimpleExpr) -> anyhow::Result<Option<String>> {
+    match value {
+        SimpleExpr::Value(value) => Ok(match sea_value_to_json_value(value) {
+            Json::Null => None,
+            Json::String(s) => Some(s),
+            json => Some(json.to_string()),
+        }),
+        _ => anyhow::bail!("cannot compare the expression {value:?}"),
+    }
+}
+
+/// Numbers are compared by value, `8` and `8.0` being the same points. Float
+/// columns only keep a single precision.
+fn same(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) if a != b => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a as f32 == b as f32,
+            _ => false,
+        },
+        _ => a == b,
+    }
+}
+
+impl fmt::Display for TableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() {
+            return writeln!(f, "{}: no changes", self.table);
+        }
+
+        writeln!(
+            f,
+            "{}: {} added, {} removed, {} changed",
+            self.table,
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+        for fields in &self.added {
+            writeln!(f, "  + {}", Display(fields))?;
+        }
+        for fields in &self.removed {
+            writeln!(f, "  - {}", Display(fields))?;
+        }
+        for change in &self.changed {
+            write!(f, "  ~ {}:", Display(&change.key))?;
+            for (column, [before, after]) in &change.columns {
+                write!(
+                    f,
+                    " {column} {} -> {}",
+                    before.as_deref().unwrap_or("NULL"),
+                    after.as_deref().unwrap_or("NULL")
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Display<'a>(&'a Fields);
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields: Vec<_> = self
+            .0
+            .iter()
+            .map(|(column, value)| format!("{column}={}", value.as_deref().unwrap_or("NULL")))
+            .collect();
+        write!(f, "{}", fields.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sqlite")]
+    use crate::testing;
+
+    fn value(value: &str) -> Option<String> {
+        Some(value.into())
+    }
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        assert!(same(&value("8"), &value("8.0")));
+        assert!(same(&value("210.383"), &value("210.38300323486328")));
+        assert!(!same(&value("8"), &value("8.5")));
+        assert!(same(&None, &None));
+        assert!(!same(&value("0"), &None));
+        assert!(same(&value("1:31.515"), &value("1:31.515")));
+        assert!(!same(&value("1:31.515"), &value("1:31.516")));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn constructor_results_leave_out_the_sprint_points() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+        let mut tx = db.transaction().unwrap();
+        testing::import(&files, tx.as_mut()).unwrap();
+
+        let input = Input::new(&files.0, None);
+        let mut resolver = Resolver::new(testing::RACE, 2026, &input);
+        let diff = constructor_results(testing::RACE, &input, &mut resolver, tx.as_mut()).unwrap();
+        assert!(diff.changed.is_empty(), "{diff}");
+        assert!(diff.added.is_empty() && diff.removed.is_empty(), "{diff}");
+
+        // The stored points do hold the sprint points
+        let diff =
+            rows::<models::ConstructorResult>(testing::RACE, &input, &mut resolver, tx.as_mut())
+                .unwrap();
+        assert_eq!(diff.changed.len(), 2, "{diff}");
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use sea_query::{Expr, Query};
+use serde::Deserialize;
+
+use crate::backend::Transaction;
+use crate::input::Input;
+use crate::resolver::season_entries;
+use crate::tables::{Drivers, Qualifying};
+
+/// Maps the race numbers used during a season to f1db driver ids.
+///
+/// Numbers are looked up in tiers, the first tier knowing a number wins:
+/// 1. the `driver_numbers` mapping file, if present with the input files
+/// 2. the entries of the race being imported (qualifying)
+/// 3. the entries of the other races of the season (results and qualifying)
+/// 4. the permanent number of the driver (`drivers.number`)
+///
+/// A number claimed by several drivers inside the same tier is an error, the
+/// mapping file can be used to settle it.
+pub struct DriverNumbers {
+    year: i32,
+    tiers: Vec<(&'static str, Tier)>,
+}
+
+type Tier = BTreeMap<u16, BTreeSet<i32>>;
+
+#[derive(Deserialize, Debug)]
+struct Mapping {
+    year: Option<i32>,
+    number: u16,
+    driver_id: i32,
+}
+
+impl DriverNumbers {
+    pub fn load(
+        race_id: i32,
+        year: i32,
+        input: &Input,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Self> {
+        let mut tiers = Vec::new();
+
+        if input.exists("driver_numbers") {
+            let mut tier = Tier::new();
+            for m in input.read::<Mapping>("driver_numbers")? {
+                if m.year.is_none_or(|y| y == year) {
+                    tier.entry(m.number).or_default().insert(m.driver_id);
+                }
+            }
+            tiers.push(("driver_numbers", tier));
+        }
+
+        tiers.push((
+            "race entries",
+            query_tier(
+                tx,
+                Query::select()
+                    .columns([Qualifying::Number, Qualifying::DriverID])
+                    .from(Qualifying::Table)
+                    .and_where(Expr::col(Qualifying::RaceID).eq(race_id))
+                    .to_owned(),
+            )?,
+        ));
+        tiers.push((
+            "season entries",
+            query_tier(tx, season_entries(year, &["number", "driverId"]))?,
+        ));
+        tiers.push((
+            "permanent numbers",
+            query_tier(
+                tx,
+                Query::select()
+                    .columns([Drivers::Number, Drivers::DriverID])
+                    .from(Drivers::Table)
+                    .to_owned(),
+            )?,
+        ));
+
+        Ok(Self { year, tiers })
+    }
+
+    pub fn driver_id(&self, number: u16) -> anyhow::Result<i32> {
+        let Some((source, candidates)) = self
+            .tiers
+            .iter()
+            .find_map(|(source, tier)| tier.get(&number).map(|c| (source, c)))
+        else {
+            anyhow::bail!("no driver found for car #{number} in {}", self.year);
+        };
+
+        match candidates.iter().collect::<Vec<_>>()[..] {
+            [driver_id] => Ok(*driver_id),
+            _ => anyhow::bail!(
+                "car #{number} is ambiguous in {} ({source}), candidates: {:?}, \
+                 add it to the driver_numbers file",
+                self.year,
+                candidates
+            ),
+        }
+    }
+}
+
+fn query_tier(tx: &mut dyn Transaction, query: sea_query::SelectStatement) -> anyhow::Result<Tier> {
+    let mut tier = Tier::new();
+    for row in tx.query(&query)? {
+        if let Some(number) = row.get_opt::<u16>(0)? {
+            tier.entry(number).or_default().insert(row.get(1)?);
+        }
+    }
+
+    Ok(tier)
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::input::Format;
+    use crate::testing;
+
+    #[test]
+    fn tiers_are_looked_up_in_order() {
+        let mut db = testing::database();
+        let mut tx = db.transaction().unwrap();
+        let folder = testing::folder();
+        let input = Input::new(&folder.0, None);
+        // Norris took #1 as his permanent number
+        tx.exec_sql("UPDATE drivers SET number = 1 WHERE driverId = 846")
+            .unwrap();
+
+        // Round 4 was entered with #1 by Verstappen
+        let numbers = DriverNumbers::load(1, 2026, &input, tx.as_mut()).unwrap();
+        assert_eq!(numbers.driver_id(1).unwrap(), 830);
+        assert_eq!(numbers.driver_id(33).unwrap(), 830);
+        assert!(numbers.driver_id(99).is_err());
+
+        // Round 5 has no entries yet, round 4 still counts for the season
+        let numbers = DriverNumbers::load(testing::RACE, 2026, &input, tx.as_mut()).unwrap();
+        assert_eq!(numbers.driver_id(1).unwrap(), 830);
+
+        std::fs::write(
+            folder.0.join("driver_numbers.csv"),
+            "year,number,driver_id\n2026,1,846\n2025,4,830\n,27,830\n,27,846\n",
+        )
+        .unwrap();
+        let numbers = DriverNumbers::load(1, 2026, &input, tx.as_mut()).unwrap();
+        assert_eq!(numbers.driver_id(1).unwrap(), 846);
+        assert_eq!(numbers.driver_id(4).unwrap(), 846);
+        let ambiguous = numbers.driver_id(27).unwrap_err().to_string();
+        assert!(ambiguous.contains("(driver_numbers)"), "{ambiguous}");
+    }
+
+    #[test]
+    fn mapping_follows_the_input_format() {
+        let mut db = testing::database();
+        let mut tx = db.transaction().unwrap();
+        let folder = testing::folder();
+        std::fs::write(
+            folder.0.join("driver_numbers.json"),
+            r#"[{"year": 2026, "number": 1, "driver_id": 846}]"#,
+        )
+        .unwrap();
+
+        let input = Input::new(&folder.0, Some(Format::Json));
+        let numbers = DriverNumbers::load(1, 2026, &input, tx.as_mut()).unwrap();
+        assert_eq!(numbers.driver_id(1).unwrap(), 846);
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use sea_query::{Alias, Expr, Func, Iden, Order, Query, SelectStatement};
+use serde::Serialize;
+
+use crate::backend::Transaction;
+use crate::importers::Importer;
+use crate::models;
+use crate::tables::*;
+
+/// Rebuilds the input records of a race from the database.
+///
+/// Records carry the ids and refs of the drivers and constructors, so the
+/// exported files are imported back without any name or number lookup.
+pub trait Exporter: Importer + Serialize {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>>;
+}
+
+/// Writes `<out>/<FILE>.csv` and returns the number of records, no file is
+/// written when the race has no rows.
+pub fn write<E: Exporter>(
+    race_id: i32,
+    out: &Path,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<usize> {
+    let records = E::export(race_id, tx)?;
+    if records.is_empty() {
+        log::info!("no rows for {}, nothing exported", E::FILE);
+        return Ok(0);
+    }
+
+    let path = out.join(format!("{}.csv", E::FILE));
+    let mut wtr =
+        csv::Writer::from_path(&path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+    for record in &records {
+        wtr.serialize(record)?;
+    }
+    wtr.flush()?;
+
+    log::info!("{} records exported to {}", records.len(), path.display());
+    Ok(records.len())
+}
+
+impl Exporter for models::LapTime {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_driver(LapTimes::Table, LapTimes::DriverID)
+            .columns([
+                (LapTimes::Table, LapTimes::Lap),
+                (LapTimes::Table, LapTimes::Position),
+                (LapTimes::Table, LapTimes::Milliseconds),
+            ])
+            .and_where(Expr::col((LapTimes::Table, LapTimes::RaceID)).eq(race_id))
+            .order_by((LapTimes::Table, LapTimes::Lap), Order::Asc)
+            .order_by((LapTimes::Table, LapTimes::Position), Order::Asc)
+            .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    driver_id: Some(row.get(0)?),
+                    driver_ref: row.get_opt(1)?,
+                    code: row.get_opt(2)?,
+                    number: None,
+                    lap: row.get(3)?,
+                    position: row.get(4)?,
+                    time: chrono::TimeDelta::milliseconds(row.get(5)?),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::PitStop {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_driver(PitStops::Table, PitStops::DriverID)
+            .columns([
+                (PitStops::Table, PitStops::Stop),
+                (PitStops::Table, PitStops::Lap),
+                (PitStops::Table, PitStops::Time),
+                (PitStops::Table, PitStops::Milliseconds),
+            ])
+            .and_where(Expr::col((PitStops::Table, PitStops::RaceID)).eq(race_id))
+            .order_by((PitStops::Table, PitStops::Time), Order::Asc)
+            .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    driver_id: Some(row.get(0)?),
+                    driver_ref: row.get_opt(1)?,
+                    code: row.get_opt(2)?,
+                    number: None,
+                    stop: row.get(3)?,
+                    lap: row.get(4)?,
+                    time: row.get(5)?,
+                    duration: chrono::TimeDelta::milliseconds(row.get(6)?),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::Qualifying {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_constructor(
+            with_driver(Qualifying::Table, Qualifying::DriverID),
+            Qualifying::Table,
+            Qualifying::ConstructorID,
+        )
+        .columns([
+            (Qualifying::Table, Qualifying::Position),
+            (Qualifying::Table, Qualifying::Number),
+            (Qualifying::Table, Qualifying::Q1),
+            (Qualifying::Table, Qualifying::Q2),
+            (Qualifying::Table, Qualifying::Q3),
+        ])
+        .and_where(Expr::col((Qualifying::Table, Qualifying::RaceID)).eq(race_id))
+        .order_by((Qualifying::Table, Qualifying::Position), Order::Asc)
+        .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    driver_id: Some(row.get(0)?),
+                    driver_ref: row.get_opt(1)?,
+                    code: row.get_opt(2)?,
+                    constructor_id: Some(row.get(3)?),
+                    constructor_ref: row.get_opt(4)?,
+                    position: row.get(5)?,
+                    number: row.get(6)?,
+                    q1: row.get_opt(7)?,
+                    q2: row.get_opt(8)?,
+                    q3: row.get_opt(9)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::RaceResult {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_constructor(
+            with_driver(Results::Table, Results::DriverID),
+            Results::Table,
+            Results::ConstructorID,
+        )
+        .columns([
+            (Results::Table, Results::Number),
+            (Results::Table, Results::Position),
+            (Results::Table, Results::Grid),
+            (Results::Table, Results::PositionText),
+            (Results::Table, Results::PositionOrder),
+            (Results::Table, Results::Points),
+            (Results::Table, Results::Laps),
+            (Results::Table, Results::Time),
+            (Results::Table, Results::Milliseconds),
+            (Results::Table, Results::FastestLap),
+            (Results::Table, Results::FastestLapTime),
+            (Results::Table, Results::Rank),
+            (Results::Table, Results::FastestLapSpeed),
+        ])
+        .and_where(Expr::col((Results::Table, Results::RaceID)).eq(race_id))
+        .order_by((Results::Table, Results::PositionOrder), Order::Asc)
+        .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    driver_id: Some(row.get(0)?),
+                    driver_ref: row.get_opt(1)?,
+                    code: row.get_opt(2)?,
+                    constructor_id: Some(row.get(3)?),
+                    constructor_ref: row.get_opt(4)?,
+                    driver_number: row.get(5)?,
+                    position: row.get_opt(6)?,
+                    grid: row.get(7)?,
+                    position_text: row.get(8)?,
+                    position_order: row.get(9)?,
+                    points: row.get(10)?,
+                    laps: row.get(11)?,
+                    time: row.get_opt(12)?,
+                    milliseconds: row.get_opt(13)?,
+                    fastest_lap: row.get_opt(14)?,
+                    fatest_lap_time: row.get_opt(15)?,
+                    rank: row.get_opt(16)?,
+                    fastest_lap_speed: row.get_opt(17)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::DriverStanding {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_driver(DriverStandings::Table, DriverStandings::DriverID)
+            .columns([
+                (DriverStandings::Table, DriverStandings::Points),
+                (DriverStandings::Table, DriverStandings::Position),
+                (DriverStandings::Table, DriverStandings::PositionText),
+                (DriverStandings::Table, DriverStandings::Wins),
+            ])
+            .and_where(Expr::col((DriverStandings::Table, DriverStandings::RaceID)).eq(race_id))
+            .order_by(
+                (DriverStandings::Table, DriverStandings::Position),
+                Order::Asc,
+            )
+            .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    driver_id: Some(row.get(0)?),
+                    driver_ref: row.get_opt(1)?,
+                    code: row.get_opt(2)?,
+                    number: None,
+                    points: row.get(3)?,
+                    position: row.get(4)?,
+                    position_text: row.get(5)?,
+                    wins: row.get(6)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::ConstructorStanding {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = with_constructor(
+            Query::select(),
+            ConstructorStandings::Table,
+            ConstructorStandings::ConstructorID,
+        )
+        .from(ConstructorStandings::Table)
+        .columns([
+            (ConstructorStandings::Table, ConstructorStandings::Points),
+            (ConstructorStandings::Table, ConstructorStandings::Position),
+            (
+                ConstructorStandings::Table,
+                ConstructorStandings::PositionText,
+            ),
+            (ConstructorStandings::Table, ConstructorStandings::Wins),
+        ])
+        .and_where(
+            Expr::col((ConstructorStandings::Table, ConstructorStandings::RaceID)).eq(race_id),
+        )
+        .order_by(
+            (ConstructorStandings::Table, ConstructorStandings::Position),
+            Order::Asc,
+        )
+        .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    constructor_id: Some(row.get(0)?),
+                    constructor_ref: row.get_opt(1)?,
+                    points: row.get(2)?,
+                    position: row.get(3)?,
+                    position_text: row.get(4)?,
+                    wins: row.get(5)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::ConstructorResult {
+    /// The sprint points merged by the import are taken out again, the file
+    /// only holds the race points.
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let sprint = sprint_points(race_id, tx)?;
+
+        let query = with_constructor(
+            Query::select(),
+            ConstructorResults::Table,
+            ConstructorResults::ConstructorID,
+        )
+        .from(ConstructorResults::Table)
+        .column((ConstructorResults::Table, ConstructorResults::Points))
+        .and_where(Expr::col((ConstructorResults::Table, ConstructorResults::RaceID)).eq(race_id))
+        .order_by(
+            (ConstructorResults::Table, ConstructorResults::ConstructorID),
+            Order::Asc,
+        )
+        .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                let constructor_id = row.get(0)?;
+                let sprint = sprint.get(&constructor_id).copied().unwrap_or_default();
+                let points = row.get::<f32>(2)? - sprint;
+                if points < 0.0 {
+                    anyhow::bail!(
+                        "constructor {constructor_id} has less points than in the sprint"
+                    );
+                }
+                Ok(Self {
+                    constructor_id: Some(constructor_id),
+                    constructor_ref: row.get_opt(1)?,
+                    points,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Exporter for models::DriverSprintResult {
+    fn export(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<Vec<Self>> {
+        let query = Query::select()
+            .column((Constructors::Table, Constructors::Name))
+            .columns([
+                (SprintResults::Table, SprintResults::Number),
+                (SprintResults::Table, SprintResults::Grid),
+                (SprintResults::Table, SprintResults::PositionText),
+                (SprintResults::Table, SprintResults::PositionOrder),
+                (SprintResults::Table, SprintResults::Points),
+                (SprintResults::Table, SprintResults::Laps),
+                (SprintResults::Table, SprintResults::Time),
+                (SprintResults::Table, SprintResults::Milliseconds),
+                (SprintResults::Table, SprintResults::FastestLap),
+                (SprintResults::Table, SprintResults::FastestLapTime),
+                (SprintResults::Table, SprintResults::FastestLapSpeed),
+            ])
+            .from(SprintResults::Table)
+            .inner_join(
+                Constructors::Table,
+                Expr::col((Constructors::Table, Constructors::ConstructorID))
+                    .equals((SprintResults::Table, SprintResults::ConstructorID)),
+            )
+            .and_where(Expr::col((SprintResults::Table, SprintResults::RaceID)).eq(race_id))
+            .order_by(
+                (SprintResults::Table, SprintResults::PositionOrder),
+                Order::Asc,
+            )
+            .to_owned();
+
+        tx.query(&query)?
+            .iter()
+            .map(|row| {
+                Ok(Self {
+                    entrant: row.get(0)?,
+                    no: row.get(1)?,
+                    grid: row.get(2)?,
+                    position: row.get(3)?,
+                    position_order: row.get(4)?,
+                    points: row.get(5)?,
+                    laps: row.get(6)?,
+                    time: row.get_opt(7)?,
+                    milliseconds: row.get_opt(8)?,
+                    fastest_lap: row.get_opt(9)?,
+                    fatest_lap_time: row.get_opt(10)?,
+                    fastest_lap_speed: row.get_opt(11)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Selects `driverId`, `driverRef` and `code` of the rows of `table`.
+fn with_driver<T, C>(table: T, column: C) -> SelectStatement
+where
+    T: Iden + 'static,
+    C: Iden + 'static,
+{
+    let name = Alias::new(table.to_string());
+
+    Query::select()
+        .columns([
+            (Drivers::Table, Drivers::DriverID),
+            (Drivers::Table, Drivers::DriverRef),
+            (Drivers::Table, Drivers::Code),
+        ])
+        .from(table)
+        .inner_join(
+            Drivers::Table,
+            Expr::col((Drivers::Table, Drivers::DriverID)).equals((name, column)),
+        )
+        .to_owned()
+}
+
+/// Adds `constructorId` and `constructorRef` to the selected columns.
+fn with_constructor<T, C>(mut query: SelectStatement, table: T, column: C) -> SelectStatement
+where
+    T: Iden + 'static,
+    C: Iden + 'static,
+{
+    query
+        .columns([
+            (Constructors::Table, Constructors::ConstructorID),
+            (Constructors::Table, Constructors::ConstructorRef),
+        ])
+        .inner_join(
+            Constructors::Table,
+            Expr::col((Constructors::Table, Constructors::ConstructorID)).equals((table, column)),
+        )
+        .to_owned()
+}
+
+/// Sprint points of the race by constructor id, merged into the race points
+/// of `constructorResults` by the import.
+pub fn sprint_points(race_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<BTreeMap<i32, f32>> {
+    tx.query(
+        &Query::select()
+            .column(SprintResults::ConstructorID)
+            .expr(Func::sum(Expr::col(SprintResults::Points)))
+            .from(SprintResults::Table)
+            .and_where(Expr::col(SprintResults::RaceID).eq(race_id))
+            .group_by_col(SprintResults::ConstructorID)
+            .to_owned(),
+    )?
+    .iter()
+    .map(|row| Ok((row.get(0)?, row.get(1)?)))
+    .collect()
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::importers::TABLES;
+    use crate::input::Input;
+    use crate::resolver::Resolver;
+    use crate::testing;
+
+    /// Asserts the files of `folder` hold exactly the rows of the race.
+    fn assert_same(folder: &testing::Folder, tx: &mut dyn Transaction) {
+        let input = Input::new(&folder.0, None);
+        let mut resolver = Resolver::new(testing::RACE, 2026, &input);
+        for table in testing::options().tables(&input, true) {
+            let Some(diff) = table.diff else {
+                continue;
+            };
+            let diff = diff(testing::RACE, &input, &mut resolver, tx).unwrap();
+            assert!(
+                diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty(),
+                "{diff}"
+            );
+        }
+    }
+
+    #[test]
+    fn exported_round_imports_back_unchanged() {
+        let mut db = testing::database();
+        let mut tx = db.transaction().unwrap();
+        testing::import(&testing::round_files(), tx.as_mut()).unwrap();
+        // Norris retired without a lap time in Q1, with half points
+        for sql in [
+            "UPDATE results SET position = NULL, positionText = 'R', points = 18.5 \
+             WHERE raceId = 2 AND driverId = 846",
+            "UPDATE qualifying SET q1 = NULL WHERE raceId = 2 AND driverId = 846",
+            "UPDATE driverStandings SET points = 43.5 WHERE raceId = 2 AND driverId = 846",
+            "UPDATE constructorStandings SET points = 43.5 WHERE raceId = 2 AND constructorId = 1",
+            "UPDATE constructorResults SET points = 25.5 WHERE raceId = 2 AND constructorId = 1",
+        ] {
+            tx.exec_sql(sql).unwrap();
+        }
+
+        let out = testing::folder();
+        for table in TABLES {
+            if let Some(export) = table.export {
+                export(testing::RACE, &out.0, tx.as_mut()).unwrap();
+            }
+        }
+        assert_same(&out, tx.as_mut());
+
+        let mut copy = testing::database();
+        let mut copy_tx = copy.transaction().unwrap();
+        testing::import(&out, copy_tx.as_mut()).unwrap();
+        assert_same(&out, copy_tx.as_mut());
+
+        let norris = copy_tx
+            .query_sql(
+                "SELECT results.position, results.points, qualifying.q1 FROM results \
+                 INNER JOIN qualifying ON qualifying.raceId = results.raceId \
+                 AND qualifying.driverId = results.driverId \
+                 WHERE results.raceId = 2 AND results.driverId = 846",
+            )
+            .unwrap();
+        assert_eq!(norris[0].0, [None, Some("18.5".into()), None]);
+    }
+}
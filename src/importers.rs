@@ -0,0 +1,834 @@
+use std::fmt::Debug;
+use std::path::Path;
+
+use sea_query::{Alias, Expr, Iden, Query, SimpleExpr, Value};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::backend::Transaction;
+use crate::bypass_duplicates;
+use crate::diff::{self, TableDiff};
+use crate::export::{self, Exporter};
+use crate::input::Input;
+use crate::journal::{Journal, Key};
+use crate::models;
+use crate::resolver::Resolver;
+use crate::tables::*;
+
+/// Imports the records of an input file into one of the f1db tables.
+///
+/// Implemented by the `models` structs, a new table only needs an
+/// implementation and an entry in [`TABLES`].
+pub trait Importer: DeserializeOwned + Debug {
+    /// Name of the input file, without its extension.
+    const FILE: &'static str;
+
+    type Table: Iden + 'static;
+    const TABLE: Self::Table;
+
+    /// Rows already in the database are skipped instead of aborting the
+    /// import.
+    const BYPASS_DUPLICATES: bool = false;
+
+    fn columns() -> Vec<Self::Table>;
+
+    /// Columns among [`Importer::columns`] identifying a row, for the journal.
+    fn key() -> Vec<Self::Table>;
+
+    /// Values of the [`Importer::columns`] for this record.
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>>;
+}
+
+type ImportFn =
+    fn(i32, &Input, &mut Resolver, &mut Journal, &mut dyn Transaction) -> anyhow::Result<Counts>;
+
+type DiffFn = fn(i32, &Input, &mut Resolver, &mut dyn Transaction) -> anyhow::Result<TableDiff>;
+
+type ExportFn = fn(i32, &Path, &mut dyn Transaction) -> anyhow::Result<usize>;
+
+/// Rows handled by a step of the import.
+#[derive(Serialize, Debug, Default, Clone, Copy)]
+pub struct Counts {
+    pub inserted: u64,
+    pub updated: u64,
+    /// Rows already in the database, left untouched.
+    pub skipped: u64,
+}
+
+impl Counts {
+    pub fn written(&self) -> u64 {
+        self.inserted + self.updated
+    }
+}
+
+/// A step of the import, run in the order of [`TABLES`].
+pub struct Table {
+    pub name: &'static str,
+    /// Input file read by the step.
+    pub file: &'static str,
+    /// Only imported on sprint weekends.
+    pub sprint: bool,
+    /// Skipped with a warning when the input file is missing, the data is
+    /// often published after the race results.
+    pub optional: bool,
+    pub import: ImportFn,
+    /// Compares the input file with the database, for the steps writing the
+    /// rows of a single file.
+    pub diff: Option<DiffFn>,
+    /// Writes the input file back from the database.
+    pub export: Option<ExportFn>,
+}
+
+const fn table<I: Exporter>(sprint: bool, optional: bool) -> Table {
+    Table {
+        name: I::FILE,
+        file: I::FILE,
+        sprint,
+        optional,
+        import: import::<I>,
+        diff: Some(diff::rows::<I>),
+        export: Some(export::write::<I>),
+    }
+}
+
+pub const TABLES: &[Table] = &[
+    table::<models::LapTime>(false, true),
+    table::<models::PitStop>(false, true),
+    table::<models::Qualifying>(false, false),
+    table::<models::RaceResult>(false, false),
+    table::<models::DriverStanding>(false, false),
+    table::<models::ConstructorStanding>(false, false),
+    Table {
+        diff: Some(diff::constructor_results),
+        ..table::<models::ConstructorResult>(false, false)
+    },
+    table::<models::DriverSprintResult>(true, false),
+    Table {
+        name: "constructor_sprint_results",
+        file: "sprint_results",
+        sprint: true,
+        optional: false,
+        import: constructor_sprint_results,
+        diff: None,
+        export: None,
+    },
+    Table {
+        name: "race_info",
+        file: "race_info",
+        sprint: false,
+        optional: true,
+        import: race_info,
+        diff: None,
+        export: None,
+    },
+];
+
+fn import<I: Importer>(
+    race_id: i32,
+    input: &Input,
+    resolver: &mut Resolver,
+    journal: &mut Journal,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<Counts> {
+    let mut counts = Counts::default();
+    for record in input.read::<I>(I::FILE)? {
+        log::trace!("inserting {}: {:?}", I::FILE, record);
+
+        let values = record.values(race_id, resolver, tx)?;
+        let key = key::<I>(&values)?;
+        let q = Query::insert()
+            .into_table(I::TABLE)
+            .columns(I::columns())
+            .values(values)?
+            .to_owned();
+
+        let inserted = if I::BYPASS_DUPLICATES {
+            bypass_duplicates!(tx.exec(&q))?.unwrap_or(0)
+        } else {
+            tx.exec(&q)?
+        };
+        if inserted > 0 {
+            journal.inserted(I::TABLE, key);
+            counts.inserted += inserted;
+        } else {
+            counts.skipped += 1;
+        }
+    }
+
+    Ok(counts)
+}
+
+fn key<I: Importer>(values: &[SimpleExpr]) -> anyhow::Result<Key> {
+    let columns: Vec<String> = I::columns().iter().map(|c| c.to_string()).collect();
+
+    I::key()
+        .iter()
+        .map(|column| {
+            let column = column.to_string();
+            let value = columns
+                .iter()
+                .position(|c| *c == column)
+                .and_then(|idx| values.get(idx));
+            match value {
+                Some(SimpleExpr::Value(value)) => Ok((column, value.clone())),
+                _ => anyhow::bail!("no value for the key column {column} of {}", I::FILE),
+            }
+        })
+        .collect()
+}
+
+impl Importer for models::LapTime {
+    const FILE: &'static str = "lap_times";
+    type Table = LapTimes;
+    const TABLE: LapTimes = LapTimes::Table;
+    const BYPASS_DUPLICATES: bool = true;
+
+    fn columns() -> Vec<LapTimes> {
+        vec![
+            LapTimes::RaceID,
+            LapTimes::DriverID,
+            LapTimes::Lap,
+            LapTimes::Position,
+            LapTimes::Time,
+            LapTimes::Milliseconds,
+        ]
+    }
+
+    fn key() -> Vec<LapTimes> {
+        vec![LapTimes::RaceID, LapTimes::DriverID, LapTimes::Lap]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        let time = format!(
+            "{}:{:02}.{:03}",
+            self.time.num_minutes(),
+            self.time.num_seconds() % 60,
+            self.time.num_milliseconds() % 1000
+        );
+
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id(&self.driver()?, tx)?.into(),
+            self.lap.into(),
+            self.position.into(),
+            time.into(),
+            self.time.num_milliseconds().into(),
+        ])
+    }
+}
+
+impl Importer for models::PitStop {
+    const FILE: &'static str = "pit_stops";
+    type Table = PitStops;
+    const TABLE: PitStops = PitStops::Table;
+
+    fn columns() -> Vec<PitStops> {
+        vec![
+            PitStops::RaceID,
+            PitStops::DriverID,
+            PitStops::Stop,
+            PitStops::Lap,
+            PitStops::Time,
+            PitStops::Duration,
+            PitStops::Milliseconds,
+        ]
+    }
+
+    fn key() -> Vec<PitStops> {
+        vec![PitStops::RaceID, PitStops::DriverID, PitStops::Stop]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        let duration = models::format_pit_stop_duration(&self.duration);
+
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id(&self.driver()?, tx)?.into(),
+            self.stop.into(),
+            self.lap.into(),
+            self.time.format("%H:%M:%S").to_string().into(),
+            duration.into(),
+            self.duration.num_milliseconds().into(),
+        ])
+    }
+}
+
+impl Importer for models::Qualifying {
+    const FILE: &'static str = "qualifying";
+    type Table = Qualifying;
+    const TABLE: Qualifying = Qualifying::Table;
+
+    fn columns() -> Vec<Qualifying> {
+        vec![
+            Qualifying::RaceID,
+            Qualifying::DriverID,
+            Qualifying::ConstructorID,
+            Qualifying::Number,
+            Qualifying::Position,
+            Qualifying::Q1,
+            Qualifying::Q2,
+            Qualifying::Q3,
+        ]
+    }
+
+    fn key() -> Vec<Qualifying> {
+        vec![Qualifying::RaceID, Qualifying::DriverID]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        // TODO: Handle status
+
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id(&self.driver()?, tx)?.into(),
+            resolver.constructor_id(&self.constructor()?, tx)?.into(),
+            self.number.into(),
+            self.position.into(),
+            self.q1.clone().into(),
+            self.q2.clone().into(),
+            self.q3.clone().into(),
+        ])
+    }
+}
+
+impl Importer for models::RaceResult {
+    const FILE: &'static str = "results";
+    type Table = Results;
+    const TABLE: Results = Results::Table;
+
+    fn columns() -> Vec<Results> {
+        vec![
+            Results::RaceID,
+            Results::DriverID,
+            Results::ConstructorID,
+            Results::Number,
+            Results::Grid,
+            Results::Position,
+            Results::PositionText,
+            Results::PositionOrder,
+            Results::Points,
+            Results::Laps,
+            Results::Time,
+            Results::Milliseconds,
+            Results::FastestLap,
+            Results::Rank,
+            Results::FastestLapTime,
+            Results::FastestLapSpeed,
+        ]
+    }
+
+    fn key() -> Vec<Results> {
+        vec![Results::RaceID, Results::DriverID, Results::ConstructorID]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        // TODO: Handle status
+
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id(&self.driver()?, tx)?.into(),
+            resolver.constructor_id(&self.constructor()?, tx)?.into(),
+            self.driver_number.into(),
+            self.grid.into(),
+            self.position.into(),
+            self.position_text.clone().into(),
+            self.position_order.into(),
+            self.points.into(),
+            self.laps.into(),
+            self.time.clone().into(),
+            self.milliseconds.clone().into(),
+            self.fastest_lap.into(),
+            self.rank.into(),
+            self.fatest_lap_time.clone().into(),
+            self.fastest_lap_speed.into(),
+        ])
+    }
+}
+
+impl Importer for models::ConstructorResult {
+    const FILE: &'static str = "constructor_results";
+    type Table = ConstructorResults;
+    const TABLE: ConstructorResults = ConstructorResults::Table;
+
+    fn columns() -> Vec<ConstructorResults> {
+        vec![
+            ConstructorResults::RaceID,
+            ConstructorResults::ConstructorID,
+            ConstructorResults::Points,
+        ]
+    }
+
+    fn key() -> Vec<ConstructorResults> {
+        vec![
+            ConstructorResults::RaceID,
+            ConstructorResults::ConstructorID,
+        ]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        Ok(vec![
+            race_id.into(),
+            resolver.constructor_id(&self.constructor()?, tx)?.into(),
+            self.points.into(),
+        ])
+    }
+}
+
+impl Importer for models::DriverStanding {
+    const FILE: &'static str = "driver_standings";
+    type Table = DriverStandings;
+    const TABLE: DriverStandings = DriverStandings::Table;
+
+    fn columns() -> Vec<DriverStandings> {
+        vec![
+            DriverStandings::RaceID,
+            DriverStandings::DriverID,
+            DriverStandings::Points,
+            DriverStandings::Position,
+            DriverStandings::PositionText,
+            DriverStandings::Wins,
+        ]
+    }
+
+    fn key() -> Vec<DriverStandings> {
+        vec![DriverStandings::RaceID, DriverStandings::DriverID]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id(&self.driver()?, tx)?.into(),
+            self.points.into(),
+            self.position.into(),
+            self.position_text.clone().into(),
+            self.wins.into(),
+        ])
+    }
+}
+
+impl Importer for models::ConstructorStanding {
+    const FILE: &'static str = "constructor_standings";
+    type Table = ConstructorStandings;
+    const TABLE: ConstructorStandings = ConstructorStandings::Table;
+
+    fn columns() -> Vec<ConstructorStandings> {
+        vec![
+            ConstructorStandings::RaceID,
+            ConstructorStandings::ConstructorID,
+            ConstructorStandings::Points,
+            ConstructorStandings::Position,
+            ConstructorStandings::PositionText,
+            ConstructorStandings::Wins,
+        ]
+    }
+
+    fn key() -> Vec<ConstructorStandings> {
+        vec![
+            ConstructorStandings::RaceID,
+            ConstructorStandings::ConstructorID,
+        ]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        Ok(vec![
+            race_id.into(),
+            resolver.constructor_id(&self.constructor()?, tx)?.into(),
+            self.points.into(),
+            self.position.into(),
+            self.position_text.clone().into(),
+            self.wins.into(),
+        ])
+    }
+}
+
+impl Importer for models::DriverSprintResult {
+    const FILE: &'static str = "sprint_results";
+    type Table = SprintResults;
+    const TABLE: SprintResults = SprintResults::Table;
+
+    fn columns() -> Vec<SprintResults> {
+        vec![
+            SprintResults::RaceID,
+            SprintResults::DriverID,
+            SprintResults::ConstructorID,
+            SprintResults::Number,
+            SprintResults::Grid,
+            SprintResults::Position,
+            SprintResults::PositionText,
+            SprintResults::PositionOrder,
+            SprintResults::Points,
+            SprintResults::Laps,
+            SprintResults::Time,
+            SprintResults::Milliseconds,
+            SprintResults::FastestLap,
+            SprintResults::FastestLapTime,
+            SprintResults::FastestLapSpeed,
+        ]
+    }
+
+    fn key() -> Vec<SprintResults> {
+        vec![SprintResults::RaceID, SprintResults::DriverID]
+    }
+
+    fn values(
+        &self,
+        race_id: i32,
+        resolver: &mut Resolver,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<Vec<SimpleExpr>> {
+        // TODO: Handle status
+
+        Ok(vec![
+            race_id.into(),
+            resolver.driver_id_by_number(self.no, tx)?.into(),
+            resolver.constructor_id(&self.constructor(), tx)?.into(),
+            self.no.into(),
+            self.grid.into(),
+            self.position.parse::<u16>().ok().into(),
+            self.position.clone().into(),
+            self.position_order.into(),
+            self.points.into(),
+            self.laps.into(),
+            self.time.clone().into(),
+            self.milliseconds.clone().into(),
+            self.fastest_lap.into(),
+            self.fatest_lap_time.clone().into(),
+            self.fastest_lap_speed.into(),
+        ])
+    }
+}
+
+/// Adds the sprint points scored by each constructor to its `constructorResults`
+/// row for the race.
+///
+/// Like f1db, there is a single constructor result per race weekend, so the
+/// sprint points are merged into the race points instead of being inserted as
+/// a second row. The points are set to the race points of the
+/// `constructor_results` file plus the sprint points, running the step again
+/// does not add the sprint points twice.
+fn constructor_sprint_results(
+    race_id: i32,
+    input: &Input,
+    resolver: &mut Resolver,
+    journal: &mut Journal,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<Counts> {
+    let mut counts = Counts::default();
+    let mut race = std::collections::BTreeMap::<i32, f32>::new();
+    for cr in input.read::<models::ConstructorResult>("constructor_results")? {
+        race.insert(resolver.constructor_id(&cr.constructor()?, tx)?, cr.points);
+    }
+    let mut sprint = std::collections::BTreeMap::<i32, f32>::new();
+    for dsr in input.read::<models::DriverSprintResult>("sprint_results")? {
+        let constructor_id = resolver.constructor_id(&dsr.constructor(), tx)?;
+        *sprint.entry(constructor_id).or_default() += dsr.points;
+    }
+
+    for (constructor_id, sprint) in sprint {
+        let points = race.get(&constructor_id).copied().unwrap_or_default() + sprint;
+        log::debug!("adding constructor sprint points: {constructor_id} -> {sprint}");
+
+        let key: Key = vec![
+            (ConstructorResults::RaceID.to_string(), race_id.into()),
+            (
+                ConstructorResults::ConstructorID.to_string(),
+                constructor_id.into(),
+            ),
+        ];
+        let existing = tx.query(
+            &Query::select()
+                .column(ConstructorResults::Points)
+                .from(ConstructorResults::Table)
+                .and_where(Expr::col(ConstructorResults::RaceID).eq(race_id))
+                .and_where(Expr::col(ConstructorResults::ConstructorID).eq(constructor_id))
+                .to_owned(),
+        )?;
+
+        if let Some(row) = existing.first() {
+            let q = Query::update()
+                .table(ConstructorResults::Table)
+                .value(ConstructorResults::Points, points)
+                .and_where(Expr::col(ConstructorResults::RaceID).eq(race_id))
+                .and_where(Expr::col(ConstructorResults::ConstructorID).eq(constructor_id))
+                .to_owned();
+
+            counts.updated += tx.exec(&q)?;
+            let old: Value = row.get_opt::<String>(0)?.into();
+            journal.updated(
+                ConstructorResults::Table,
+                key,
+                vec![(ConstructorResults::Points.to_string(), old)],
+            );
+            continue;
+        }
+
+        // The constructor did not score on Sunday and has no race row yet
+        let q = Query::insert()
+            .into_table(ConstructorResults::Table)
+            .columns([
+                ConstructorResults::RaceID,
+                ConstructorResults::ConstructorID,
+                ConstructorResults::Points,
+            ])
+            .values([race_id.into(), constructor_id.into(), points.into()])?
+            .to_owned();
+
+        counts.inserted += tx.exec(&q)?;
+        journal.inserted(ConstructorResults::Table, key);
+    }
+
+    Ok(counts)
+}
+
+/// Writes the actual schedule and the outcome of the weekend to its `races`
+/// row.
+///
+/// A race is shortened when fewer laps than scheduled were completed, the
+/// points awarded may then differ from the usual scale.
+fn race_info(
+    race_id: i32,
+    input: &Input,
+    _resolver: &mut Resolver,
+    journal: &mut Journal,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<Counts> {
+    let [info] = &input.read::<models::RaceInfo>("race_info")?[..] else {
+        anyhow::bail!("race_info must hold a single row");
+    };
+
+    let date = |value: &Option<String>| -> anyhow::Result<Option<Value>> {
+        value
+            .as_deref()
+            .map(|v| {
+                Ok(chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d")?
+                    .to_string()
+                    .into())
+            })
+            .transpose()
+    };
+    let time = |value: &Option<String>| -> anyhow::Result<Option<Value>> {
+        value
+            .as_deref()
+            .map(|v| {
+                Ok(chrono::NaiveTime::parse_from_str(v, "%H:%M:%S")?
+                    .to_string()
+                    .into())
+            })
+            .transpose()
+    };
+    let shortened = match (info.scheduled_laps, info.completed_laps) {
+        (Some(scheduled), Some(completed)) => Some(completed < scheduled),
+        _ => None,
+    };
+
+    let fields = [
+        (Races::Date, date(&info.date)?),
+        (Races::Time, time(&info.time)?),
+        (Races::Fp1Date, date(&info.fp1_date)?),
+        (Races::Fp1Time, time(&info.fp1_time)?),
+        (Races::Fp2Date, date(&info.fp2_date)?),
+        (Races::Fp2Time, time(&info.fp2_time)?),
+        (Races::Fp3Date, date(&info.fp3_date)?),
+        (Races::Fp3Time, time(&info.fp3_time)?),
+        (Races::QualiDate, date(&info.quali_date)?),
+        (Races::QualiTime, time(&info.quali_time)?),
+        (Races::SprintDate, date(&info.sprint_date)?),
+        (Races::SprintTime, time(&info.sprint_time)?),
+        (Races::ScheduledLaps, info.scheduled_laps.map(Into::into)),
+        (Races::CompletedLaps, info.completed_laps.map(Into::into)),
+        (Races::RedFlagged, info.red_flagged.map(Into::into)),
+        (Races::Shortened, shortened.map(Into::into)),
+    ];
+    let fields: Vec<(Races, Value)> = fields
+        .into_iter()
+        .filter_map(|(column, value)| Some((column, value?)))
+        .collect();
+    if fields.is_empty() {
+        log::warn!("race_info is empty, nothing to update");
+        return Ok(Counts::default());
+    }
+
+    let old = tx.query(
+        &Query::select()
+            .columns(
+                fields
+                    .iter()
+                    .map(|(column, _)| column.to_string())
+                    .map(Alias::new),
+            )
+            .from(Races::Table)
+            .and_where(Expr::col(Races::RaceID).eq(race_id))
+            .to_owned(),
+    )?;
+    let old = old
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("race {race_id} not found"))?
+        .0
+        .iter()
+        .zip(&fields)
+        .map(|(value, (column, _))| (column.to_string(), value.clone().into()))
+        .collect();
+
+    log::debug!("updating race {race_id}: {info:?}");
+    let updated = tx.exec(
+        Query::update()
+            .table(Races::Table)
+            .values(
+                fields
+                    .into_iter()
+                    .map(|(column, value)| (column, value.into())),
+            )
+            .and_where(Expr::col(Races::RaceID).eq(race_id)),
+    )?;
+    journal.updated(
+        Races::Table,
+        vec![(Races::RaceID.to_string(), race_id.into())],
+        old,
+    );
+
+    Ok(Counts {
+        updated,
+        ..Counts::default()
+    })
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn constructor_points(tx: &mut dyn Transaction) -> Vec<(i32, f64)> {
+        tx.query(
+            &Query::select()
+                .columns([
+                    ConstructorResults::ConstructorID,
+                    ConstructorResults::Points,
+                ])
+                .from(ConstructorResults::Table)
+                .and_where(Expr::col(ConstructorResults::RaceID).eq(testing::RACE))
+                .order_by(ConstructorResults::ConstructorID, sea_query::Order::Asc)
+                .to_owned(),
+        )
+        .unwrap()
+        .iter()
+        .map(|row| (row.get(0).unwrap(), row.get(1).unwrap()))
+        .collect()
+    }
+
+    #[test]
+    fn sprint_points_are_merged_once() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+        let mut tx = db.transaction().unwrap();
+        testing::import(&files, tx.as_mut()).unwrap();
+        assert_eq!(constructor_points(tx.as_mut()), [(1, 25.0), (9, 33.0)]);
+
+        let input = Input::new(&files.0, None);
+        let mut resolver = Resolver::new(testing::RACE, 2026, &input);
+        let counts = constructor_sprint_results(
+            testing::RACE,
+            &input,
+            &mut resolver,
+            &mut Journal::default(),
+            tx.as_mut(),
+        )
+        .unwrap();
+        assert_eq!(counts.updated, 2);
+        assert_eq!(constructor_points(tx.as_mut()), [(1, 25.0), (9, 33.0)]);
+    }
+
+    fn race(tx: &mut dyn Transaction) -> Vec<Option<String>> {
+        let query = Query::select()
+            .columns([
+                Races::Date,
+                Races::Time,
+                Races::ScheduledLaps,
+                Races::CompletedLaps,
+                Races::RedFlagged,
+                Races::Shortened,
+            ])
+            .from(Races::Table)
+            .and_where(Expr::col(Races::RaceID).eq(testing::RACE))
+            .to_owned();
+        tx.query(&query).unwrap().remove(0).0
+    }
+
+    #[test]
+    fn race_info_updates_the_race_until_rolled_back() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+        // Delayed by a day, then stopped by a red flag after 3 laps
+        std::fs::write(
+            files.0.join("race_info.csv"),
+            "date,time,scheduled_laps,completed_laps,red_flagged\n\
+             2026-05-18,14:00:00,63,3,true\n",
+        )
+        .unwrap();
+
+        let mut tx = db.transaction().unwrap();
+        let before = race(tx.as_mut());
+        testing::import(&files, tx.as_mut()).unwrap();
+        assert_eq!(
+            race(tx.as_mut()),
+            [
+                Some("2026-05-18".into()),
+                Some("14:00:00".into()),
+                Some("63".into()),
+                Some("3".into()),
+                Some("1".into()),
+                Some("1".into())
+            ]
+        );
+        tx.commit().unwrap();
+
+        crate::journal::rollback(
+            db.as_mut(),
+            crate::journal::Target::Race {
+                year: 2026,
+                round: 5,
+            },
+        )
+        .unwrap();
+        let mut tx = db.transaction().unwrap();
+        assert_eq!(race(tx.as_mut()), before);
+    }
+}
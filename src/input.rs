@@ -0,0 +1,414 @@
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+/// Format of the files read by the importers.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Csv files whose headers match the `models` fields
+    Csv,
+    /// Json arrays of objects whose keys match the `models` fields
+    Json,
+    /// Json responses saved from an Ergast compatible API (`MRData`)
+    Ergast,
+}
+
+/// Reads the input files of a round, whatever their format.
+///
+/// Files are named after the table they feed (`results`, `lap_times`, ...).
+/// When no format is forced, `<name>.csv` is read if it exists and
+/// `<name>.json` otherwise, json files holding an `MRData` object being
+/// treated as Ergast responses. The files Ergast has no answer for, like
+/// `constructor_results`, stay csv or json when the Ergast format is forced.
+pub struct Input<'a> {
+    base_path: &'a std::path::Path,
+    format: Option<Format>,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(base_path: &'a std::path::Path, format: Option<Format>) -> Self {
+        Self { base_path, format }
+    }
+
+    /// Whether the file exists in any of the accepted formats.
+    pub fn exists(&self, name: &str) -> bool {
+        self.resolve(name).1.exists()
+    }
+
+    pub fn read<T: DeserializeOwned>(&self, name: &str) -> anyhow::Result<Vec<T>> {
+        let (format, path) = self.resolve(name);
+
+        match format {
+            Format::Csv => {
+                let mut rdr = csv::Reader::from_path(&path)
+                    .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+                Ok(rdr.deserialize().collect::<Result<_, _>>()?)
+            }
+            Format::Json | Format::Ergast => {
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+                let value: Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+
+                let rows = match value.get("MRData") {
+                    Some(data) => ergast(name, data)?,
+                    None if format == Format::Ergast => {
+                        anyhow::bail!("{}: missing MRData", path.display())
+                    }
+                    None => serde_json::from_value(value)?,
+                };
+
+                rows.into_iter()
+                    .map(|row| Ok(serde_json::from_value(row)?))
+                    .collect()
+            }
+        }
+    }
+
+    /// Sha-256 of the file [`Input::read`] would read, in hex.
+    pub fn hash(&self, name: &str) -> anyhow::Result<String> {
+        let (_, path) = self.resolve(name);
+        let bytes = std::fs::read(&path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+
+        Ok(format!("{:x}", Sha256::digest(bytes)))
+    }
+
+    fn resolve(&self, name: &str) -> (Format, PathBuf) {
+        let csv = self.base_path.join(format!("{name}.csv"));
+        let json = self.base_path.join(format!("{name}.json"));
+
+        match self.format {
+            Some(Format::Csv) => (Format::Csv, csv),
+            // Files Ergast has no answer for are read as when no format is forced
+            Some(Format::Ergast) if shape(name).is_none() && csv.exists() => (Format::Csv, csv),
+            Some(Format::Ergast) if shape(name).is_none() => (Format::Json, json),
+            Some(format) => (format, json),
+            None if csv.exists() => (Format::Csv, csv),
+            None => (Format::Json, json),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Str,
+    Num,
+}
+
+use Kind::*;
+
+/// Where the rows of a table live in an Ergast response and how their fields
+/// map to the `models` fields. `../` walks up to the enclosing object.
+struct Shape {
+    rows: &'static [&'static str],
+    fields: &'static [(&'static str, &'static str, Kind)],
+}
+
+const RESULTS: Shape = Shape {
+    rows: &["RaceTable", "Races", "Results"],
+    fields: &[
+        ("driver_ref", "Driver.driverId", Str),
+        ("constructor_ref", "Constructor.constructorId", Str),
+        ("driver_number", "number", Num),
+        ("position", "position", Num),
+        ("grid", "grid", Num),
+        ("position_text", "positionText", Str),
+        ("position_order", "position", Num),
+        ("points", "points", Num),
+        ("laps", "laps", Num),
+        ("time", "Time.time", Str),
+        ("milliseconds", "Time.millis", Str),
+        ("fastest_lap", "FastestLap.lap", Num),
+        ("fatest_lap_time", "FastestLap.Time.time", Str),
+        ("rank", "FastestLap.rank", Num),
+        ("fastest_lap_speed", "FastestLap.AverageSpeed.speed", Num),
+    ],
+};
+
+const SPRINT_RESULTS: Shape = Shape {
+    rows: &["RaceTable", "Races", "SprintResults"],
+    fields: &[
+        ("no", "number", Num),
+        ("entrant", "Constructor.name", Str),
+        ("grid", "grid", Num),
+        ("position", "positionText", Str),
+        ("positionOrder", "position", Num),
+        ("points", "points", Num),
+        ("laps", "laps", Num),
+        ("time", "Time.time", Str),
+        ("milliseconds", "Time.millis", Str),
+        ("fastestLap", "FastestLap.lap", Num),
+        ("fastestLapTime", "FastestLap.Time.time", Str),
+        ("fastestLapSpeed", "FastestLap.AverageSpeed.speed", Num),
+    ],
+};
+
+const QUALIFYING: Shape = Shape {
+    rows: &["RaceTable", "Races", "QualifyingResults"],
+    fields: &[
+        ("driver_ref", "Driver.driverId", Str),
+        ("constructor_ref", "Constructor.constructorId", Str),
+        ("position", "position", Num),
+        ("number", "number", Num),
+        ("q1", "Q1", Str),
+        ("q2", "Q2", Str),
+        ("q3", "Q3", Str),
+    ],
+};
+
+const LAP_TIMES: Shape = Shape {
+    rows: &["RaceTable", "Races", "Laps", "Timings"],
+    fields: &[
+        ("driver_ref", "driverId", Str),
+        ("lap", "../number", Num),
+        ("position", "position", Num),
+        ("time", "time", Str),
+    ],
+};
+
+const PIT_STOPS: Shape = Shape {
+    rows: &["RaceTable", "Races", "PitStops"],
+    fields: &[
+        ("driver_ref", "driverId", Str),
+        ("stop", "stop", Num),
+        ("lap", "lap", Num),
+        ("time", "time", Str),
+        ("duration", "duration", Str),
+    ],
+};
+
+const DRIVER_STANDINGS: Shape = Shape {
+    rows: &["StandingsTable", "StandingsLists", "DriverStandings"],
+    fields: &[
+        ("driver_ref", "Driver.driverId", Str),
+        ("points", "points", Num),
+        ("position", "position", Num),
+        ("position_text", "positionText", Str),
+        ("wins", "wins", Num),
+    ],
+};
+
+const CONSTRUCTOR_STANDINGS: Shape = Shape {
+    rows: &["StandingsTable", "StandingsLists", "ConstructorStandings"],
+    fields: &[
+        ("constructor_ref", "Constructor.constructorId", Str),
+        ("points", "points", Num),
+        ("position", "position", Num),
+        ("position_text", "positionText", Str),
+        ("wins", "wins", Num),
+    ],
+};
+
+fn shape(name: &str) -> Option<Shape> {
+    match name {
+        "results" => Some(RESULTS),
+        "sprint_results" => Some(SPRINT_RESULTS),
+        "qualifying" => Some(QUALIFYING),
+        "lap_times" => Some(LAP_TIMES),
+        "pit_stops" => Some(PIT_STOPS),
+        "driver_standings" => Some(DRIVER_STANDINGS),
+        "constructor_standings" => Some(CONSTRUCTOR_STANDINGS),
+        _ => None,
+    }
+}
+
+fn ergast(name: &str, data: &Value) -> anyhow::Result<Vec<Value>> {
+    let Some(shape) = shape(name) else {
+        anyhow::bail!("{name} is not available in the Ergast format");
+    };
+
+    let mut rows = vec![vec![data]];
+    for key in shape.rows {
+        rows = rows
+            .into_iter()
+            .flat_map(|ancestors| {
+                let children = match ancestors.last().unwrap().get(key) {
+                    Some(Value::Array(children)) => children.iter().collect(),
+                    Some(child) => vec![child],
+                    None => vec![],
+                };
+                children.into_iter().map(move |child| {
+                    let mut ancestors = ancestors.clone();
+                    ancestors.push(child);
+                    ancestors
+                })
+            })
+            .collect();
+    }
+
+    rows.into_iter()
+        .map(|ancestors| {
+            let mut row = Map::new();
+            for (field, path, kind) in shape.fields {
+                let mut path = *path;
+                let mut depth = ancestors.len() - 1;
+                while let Some(rest) = path.strip_prefix("../") {
+                    path = rest;
+                    depth -= 1;
+                }
+
+                let value = path
+                    .split('.')
+                    .try_fold(ancestors[depth], |v, key| v.get(key));
+                let value = match (value, kind) {
+                    (None | Some(Value::Null), _) => Value::Null,
+                    (Some(Value::String(s)), Num) => serde_json::from_str(s)
+                        .map_err(|_| anyhow::anyhow!("{name}: {path} is not a number ({s})"))?,
+                    (Some(v), _) => v.clone(),
+                };
+                row.insert(field.to_string(), value);
+            }
+
+            Ok(Value::Object(row))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{models, testing};
+
+    fn rows<T: DeserializeOwned>(name: &str, data: Value) -> Vec<T> {
+        ergast(name, &data)
+            .unwrap()
+            .into_iter()
+            .map(|row| serde_json::from_value(row).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn lap_times_take_the_lap_from_their_parent() {
+        let laps = rows::<models::LapTime>(
+            "lap_times",
+            json!({"RaceTable": {"Races": [{"Laps": [
+                {"number": "1", "Timings": [
+                    {"driverId": "max_verstappen", "position": "1", "time": "1:36.138"},
+                    {"driverId": "norris", "position": "2", "time": "1:37.012"}
+                ]},
+                {"number": "2", "Timings": [
+                    {"driverId": "max_verstappen", "position": "1", "time": "1:31.515"}
+                ]}
+            ]}]}}),
+        );
+
+        assert_eq!(
+            laps.iter()
+                .map(|lap| (
+                    lap.driver_ref.as_deref().unwrap(),
+                    lap.lap,
+                    lap.position,
+                    lap.time.num_milliseconds()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("max_verstappen", 1, 1, 96_138),
+                ("norris", 1, 2, 97_012),
+                ("max_verstappen", 2, 1, 91_515)
+            ]
+        );
+    }
+
+    #[test]
+    fn results_flatten_the_nested_fields() {
+        let results = rows::<models::RaceResult>(
+            "results",
+            json!({"RaceTable": {"Races": [{"Results": [
+                {
+                    "number": "1", "position": "1", "positionText": "1", "points": "25",
+                    "Driver": {"driverId": "max_verstappen"},
+                    "Constructor": {"constructorId": "red_bull"},
+                    "grid": "1", "laps": "57",
+                    "Time": {"millis": "5504742", "time": "1:31:44.742"},
+                    "FastestLap": {"rank": "1", "lap": "39", "Time": {"time": "1:32.608"},
+                        "AverageSpeed": {"units": "kph", "speed": "210.383"}}
+                },
+                {
+                    "number": "2", "position": "20", "positionText": "R", "points": "0",
+                    "Driver": {"driverId": "sargeant"},
+                    "Constructor": {"constructorId": "williams"},
+                    "grid": "20", "laps": "0"
+                }
+            ]}]}}),
+        );
+
+        let winner = &results[0];
+        assert_eq!(winner.driver_ref.as_deref(), Some("max_verstappen"));
+        assert_eq!(winner.constructor_ref.as_deref(), Some("red_bull"));
+        assert_eq!(winner.position_order, 1);
+        assert_eq!(winner.milliseconds.as_deref(), Some("5504742"));
+        assert_eq!(winner.fatest_lap_time.as_deref(), Some("1:32.608"));
+        assert_eq!(winner.fastest_lap_speed, Some(210.383));
+
+        let retired = &results[1];
+        assert_eq!(retired.position_text, "R");
+        assert_eq!(retired.time, None);
+        assert_eq!(retired.fastest_lap, None);
+    }
+
+    #[test]
+    fn sprint_results_use_the_entrant_name() {
+        let results = rows::<models::DriverSprintResult>(
+            "sprint_results",
+            json!({"RaceTable": {"Races": [{"SprintResults": [{
+                "number": "4", "position": "1", "positionText": "1", "points": "8",
+                "Constructor": {"constructorId": "mclaren", "name": "McLaren"},
+                "grid": "2", "laps": "19"
+            }]}]}}),
+        );
+
+        assert_eq!(results[0].no, 4);
+        assert_eq!(results[0].entrant, "McLaren");
+        assert_eq!(results[0].position, "1");
+    }
+
+    #[test]
+    fn empty_responses_have_no_rows() {
+        let data = json!({"StandingsTable": {"StandingsLists": []}});
+        assert!(ergast("driver_standings", &data).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_tables_and_bad_numbers_fail() {
+        let data = json!({"RaceTable": {"Races": [{"PitStops": [
+            {"driverId": "norris", "stop": "one", "lap": "12", "time": "15:20:00",
+                "duration": "22.045"}
+        ]}]}});
+
+        assert!(ergast("constructor_results", &data).is_err());
+        assert!(ergast("pit_stops", &data)
+            .unwrap_err()
+            .to_string()
+            .contains("stop is not a number (one)"));
+    }
+
+    #[test]
+    fn forced_ergast_reads_the_other_files_as_they_are() {
+        let folder = testing::folder();
+        std::fs::write(
+            folder.0.join("results.json"),
+            r#"{"MRData": {"RaceTable": {"Races": []}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            folder.0.join("constructor_results.csv"),
+            "constructor_ref,points\nmclaren,18\n",
+        )
+        .unwrap();
+
+        let input = Input::new(&folder.0, Some(Format::Ergast));
+        assert!(input.exists("results") && input.exists("constructor_results"));
+        assert!(input
+            .read::<models::RaceResult>("results")
+            .unwrap()
+            .is_empty());
+        let points = input
+            .read::<models::ConstructorResult>("constructor_results")
+            .unwrap();
+        assert_eq!(points[0].points, 18.0);
+        assert!(!input.exists("constructor_sprint_results"));
+    }
+}
@@ -0,0 +1,210 @@
+use sea_query::{
+    value::sea_value_to_json_value, Alias, Expr, Iden, Order, Query, SimpleExpr, Value,
+};
+use serde_json::{Map, Value as Json};
+
+use crate::backend::{Backend, Transaction};
+use crate::tables::{UpdaterJournal, UpdaterRuns};
+
+/// Columns and values identifying a row.
+pub type Key = Vec<(String, Value)>;
+
+/// Rows written during an import, stored in `updaterJournal` with the run so
+/// it can be rolled back.
+#[derive(Debug, Default)]
+pub struct Journal(Vec<Entry>);
+
+#[derive(Debug)]
+struct Entry {
+    table: String,
+    key: Map<String, Json>,
+    /// Values the run overwrote, `None` for an inserted row.
+    old: Option<Map<String, Json>>,
+}
+
+impl Journal {
+    pub fn inserted<T: Iden>(&mut self, table: T, key: Key) {
+        self.0.push(Entry {
+            table: table.to_string(),
+            key: to_json(key),
+            old: None,
+        });
+    }
+
+    pub fn updated<T: Iden>(&mut self, table: T, key: Key, old: Key) {
+        self.0.push(Entry {
+            table: table.to_string(),
+            key: to_json(key),
+            old: Some(to_json(old)),
+        });
+    }
+
+    pub fn write(&self, run_id: i32, tx: &mut dyn Transaction) -> anyhow::Result<()> {
+        for entry in &self.0 {
+            tx.exec(
+                Query::insert()
+                    .into_table(UpdaterJournal::Table)
+                    .columns([
+                        UpdaterJournal::RunID,
+                        UpdaterJournal::TableName,
+                        UpdaterJournal::RowKey,
+                        UpdaterJournal::OldValues,
+                    ])
+                    .values([
+                        run_id.into(),
+                        entry.table.clone().into(),
+                        Json::Object(entry.key.clone()).to_string().into(),
+                        entry
+                            .old
+                            .clone()
+                            .map(|old| Json::Object(old).to_string())
+                            .into(),
+                    ])?,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Run to roll back.
+#[derive(Debug, Clone)]
+pub enum Target {
+    Run(i32),
+    /// Latest run of the round.
+    Race {
+        year: i32,
+        round: u16,
+    },
+}
+
+/// Deletes the rows inserted by a run and restores the values it overwrote.
+///
+/// Only the latest run of a race that was not rolled back yet can be rolled
+/// back, the rows of a later run could depend on it.
+pub fn rollback(db: &mut dyn Backend, target: Target) -> anyhow::Result<()> {
+    let mut tx = db.transaction()?;
+
+    let mut query = Query::select()
+        .columns([UpdaterRuns::RunID, UpdaterRuns::RaceID])
+        .from(UpdaterRuns::Table)
+        .and_where(Expr::col(UpdaterRuns::RolledBackAt).is_null())
+        .order_by(UpdaterRuns::RunID, Order::Desc)
+        .limit(1)
+        .to_owned();
+    match target {
+        Target::Run(run_id) => query.and_where(Expr::col(UpdaterRuns::RunID).eq(run_id)),
+        Target::Race { year, round } => query
+            .and_where(Expr::col(UpdaterRuns::Year).eq(year))
+            .and_where(Expr::col(UpdaterRuns::Round).eq(round)),
+    };
+    let Some(row) = tx.query(&query)?.into_iter().next() else {
+        anyhow::bail!("no run to roll back for {target:?}");
+    };
+    let (run_id, race_id): (i32, i32) = (row.get(0)?, row.get(1)?);
+
+    let later = tx.query(
+        &Query::select()
+            .column(UpdaterRuns::RunID)
+            .from(UpdaterRuns::Table)
+            .and_where(Expr::col(UpdaterRuns::RaceID).eq(race_id))
+            .and_where(Expr::col(UpdaterRuns::RunID).gt(run_id))
+            .and_where(Expr::col(UpdaterRuns::RolledBackAt).is_null())
+            .to_owned(),
+    )?;
+    if let Some(row) = later.first() {
+        anyhow::bail!(
+            "run {} of the same race must be rolled back first",
+            row.get::<i32>(0)?
+        );
+    }
+
+    let entries = tx.query(
+        &Query::select()
+            .columns([
+                UpdaterJournal::TableName,
+                UpdaterJournal::RowKey,
+                UpdaterJournal::OldValues,
+            ])
+            .from(UpdaterJournal::Table)
+            .and_where(Expr::col(UpdaterJournal::RunID).eq(run_id))
+            .order_by(UpdaterJournal::EntryID, Order::Desc)
+            .to_owned(),
+    )?;
+
+    for entry in &entries {
+        let table = Alias::new(entry.get::<String>(0)?);
+        let key: Map<String, Json> = serde_json::from_str(&entry.get::<String>(1)?)?;
+        let condition = key
+            .iter()
+            .map(|(column, value)| match from_json(value) {
+                Some(value) => Expr::col(Alias::new(column)).eq(value),
+                None => Expr::col(Alias::new(column)).is_null(),
+            })
+            .reduce(SimpleExpr::and)
+            .ok_or_else(|| anyhow::anyhow!("empty key in the journal of run {run_id}"))?;
+
+        let affected = match entry.get_opt::<String>(2)? {
+            None => tx.exec(
+                Query::delete()
+                    .from_table(table.clone())
+                    .and_where(condition),
+            )?,
+            Some(old) => {
+                let old: Map<String, Json> = serde_json::from_str(&old)?;
+                tx.exec(
+                    Query::update()
+                        .table(table.clone())
+                        .values(old.iter().map(|(column, value)| {
+                            (
+                                Alias::new(column),
+                                from_json(value).unwrap_or(Value::String(None)).into(),
+                            )
+                        }))
+                        .and_where(condition),
+                )?
+            }
+        };
+
+        if affected != 1 {
+            anyhow::bail!(
+                "{} row(s) of {} match {key:?}, it changed since run {run_id}",
+                affected,
+                table.to_string()
+            );
+        }
+    }
+
+    tx.exec(
+        Query::update()
+            .table(UpdaterRuns::Table)
+            .value(
+                UpdaterRuns::RolledBackAt,
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            )
+            .and_where(Expr::col(UpdaterRuns::RunID).eq(run_id)),
+    )?;
+    tx.commit()?;
+    log::info!("run {run_id} rolled back, {} rows reverted", entries.len());
+
+    Ok(())
+}
+
+fn to_json(key: Key) -> Map<String, Json> {
+    key.into_iter()
+        .map(|(column, value)| (column, sea_value_to_json_value(&value)))
+        .collect()
+}
+
+fn from_json(value: &Json) -> Option<Value> {
+    match value {
+        Json::Null => None,
+        Json::Bool(b) => Some((*b).into()),
+        Json::Number(n) => Some(match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64()?.into(),
+        }),
+        Json::String(s) => Some(s.clone().into()),
+        _ => Some(value.to_string().into()),
+    }
+}
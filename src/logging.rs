@@ -0,0 +1,72 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use simple_logger::SimpleLogger;
+
+/// Format of the log lines, written to stderr.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// A json object per line, for log aggregators
+    Json,
+}
+
+/// Sets up the logger.
+///
+/// The level is `info` unless `--verbose` or `--quiet` is given, or
+/// `RUST_LOG` is set. `RUST_LOG` also takes per module levels with the text
+/// format.
+pub fn init(verbose: u8, quiet: u8, format: LogFormat) -> anyhow::Result<()> {
+    let flags = match (verbose, quiet) {
+        (0, 0) => None,
+        (1, _) => Some(LevelFilter::Debug),
+        (_, 0) => Some(LevelFilter::Trace),
+        (_, 1) => Some(LevelFilter::Warn),
+        _ => Some(LevelFilter::Error),
+    };
+    let env = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok());
+    let level = flags.or(env).unwrap_or(LevelFilter::Info);
+
+    match format {
+        LogFormat::Text => {
+            let logger = SimpleLogger::new().with_level(level);
+            match flags {
+                Some(_) => logger.init()?,
+                None => logger.env().init()?,
+            }
+        }
+        LogFormat::Json => {
+            log::set_boxed_logger(Box::new(JsonLogger { level }))?;
+            log::set_max_level(level);
+        }
+    }
+
+    Ok(())
+}
+
+struct JsonLogger {
+    level: LevelFilter,
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        });
+        eprintln!("{line}");
+    }
+
+    fn flush(&self) {}
+}
@@ -1,9 +1,11 @@
+/// Turns a duplicate key error into `Ok(None)`, the statement having been
+/// skipped.
 #[macro_export]
 macro_rules! bypass_duplicates {
     ($res:expr) => {
         match $res {
-            Ok(_) => Ok(()),
-            Err(mysql::Error::MySqlError(e)) if e.code == 1062 => Ok(()),
+            Ok(value) => Ok(Some(value)),
+            Err(e) if $crate::backend::is_duplicate(&e) => Ok(None),
             Err(e) => Err(e),
         }
     };
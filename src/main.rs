@@ -1,525 +1,625 @@
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use chrono::Datelike;
-use mysql::{prelude::*, Transaction};
-use sea_query::{MysqlQueryBuilder, Query};
-
+use sea_query::{Expr, Query};
+
+mod backend;
+mod checks;
+mod cli;
+mod constructor_names;
+mod diff;
+mod driver_numbers;
+mod export;
+mod importers;
+mod input;
+mod journal;
+mod logging;
 mod macros;
+mod migrations;
 mod models;
+mod report;
+mod resolver;
+mod runs;
+mod schema;
 mod tables;
-
-use simple_logger::SimpleLogger;
-use tables::*;
+#[cfg(test)]
+mod testing;
+
+use anyhow::Context;
+use backend::{Backend, Transaction};
+use cli::{Command, DiffArgs, ImportArgs, ImportOptions, Output, SeasonArgs};
+use input::{Format, Input};
+use report::Report;
+use resolver::Resolver;
+use runs::Run;
+use tables::Races;
 
 fn main() -> anyhow::Result<()> {
-    SimpleLogger::new().init()?;
+    let cli = cli::Cli::try_parse_args(env::args_os()).unwrap_or_else(|e| e.exit());
+    logging::init(cli.verbose, cli.quiet, cli.log_format)?;
 
     log::info!("starting with args: {:?}", env::args());
-    let base_path = env::var("F1_SQL_UPDATER_CSV_FOLDER").unwrap_or("csv".into());
-    let base_path = std::path::Path::new(&base_path);
-    let round = env::args().nth(1).unwrap().parse::<u16>()?;
-    let is_sprint = env::args().nth(2).unwrap().parse::<bool>()?;
-    let year = chrono::Utc::now().year();
-
-    let user = env::var("MYSQL_USER").unwrap_or("user".into());
-    let password = env::var("MYSQL_PWD").unwrap_or("password".into());
-    let port = env::var("MYSQL_TCP_PORT")
-        .map(|port| port.parse().unwrap_or(3306))
-        .unwrap_or(3306);
-    let db_name = env::var("MYSQL_DATABASE").unwrap_or("f1db".into());
-    let mut conn = mysql::Conn::new(
-        mysql::OptsBuilder::new()
-            .ip_or_hostname("localhost".into())
-            .tcp_port(port)
-            .user(Some(user))
-            .pass(Some(password))
-            .db_name(Some(db_name)),
-    )?;
-
-    let race_id = *conn
-        .query_map(
-            format!("SELECT raceId FROM races WHERE round = {round} AND year = {year}"),
-            |race_id: i32| race_id,
-        )?
-        .first()
-        .expect("race not found");
-
-    let mut tx = conn.start_transaction(mysql::TxOpts::default())?;
-
-    lap_times(race_id, &base_path, &mut tx)?;
-    pit_stops(race_id, &base_path, &mut tx)?;
-    qualifying_results(race_id, &base_path, &mut tx)?;
-    results(race_id, &base_path, &mut tx)?;
-    driver_standings(race_id, &base_path, &mut tx)?;
-    constructor_standings(race_id, &base_path, &mut tx)?;
-    constructor_results(race_id, base_path, &mut tx)?;
-
-    if is_sprint {
-        // constructor_sprint_results(race_id, &mut tx)?;
-        // sprint_lap_times(race_id, &mut tx)?;
-        // driver_sprint_results(race_id, &mut tx)?;
+    let mut db = backend::connect(cli.backend)?;
+
+    match cli.command {
+        Some(Command::InitSchema) => schema::init(db.as_mut()),
+        Some(Command::Migrate { to }) => migrations::migrate(db.as_mut(), to),
+        Some(Command::Rollback(target)) => journal::rollback(db.as_mut(), target.into()),
+        Some(Command::ImportSeason(args)) => import_season(&args, db.as_mut()),
+        Some(Command::Diff(args)) => diff(&args, db.as_mut()),
+        Some(Command::Export { year, round, out }) => export(year, round, &out, db.as_mut()),
+        None => import(&cli.import, db.as_mut()),
     }
+}
 
+fn import(args: &ImportArgs, db: &mut dyn Backend) -> anyhow::Result<()> {
+    let mut report = Report::new();
+    let result = import_into(args, db, &mut report);
+    report.save(args.options.report.as_deref(), &result)?;
+    result
+}
+
+fn import_into(args: &ImportArgs, db: &mut dyn Backend, report: &mut Report) -> anyhow::Result<()> {
+    let round = args.round.expect("round is required");
+    let year = chrono::Utc::now().year();
+    let report = report.round(year, round);
+
+    let mut tx = db.transaction()?;
+    check_schema(&args.options, tx.as_mut())?;
+    import_round(
+        &args.options,
+        &base_path(),
+        year,
+        round,
+        args.is_sprint,
+        report,
+        tx.as_mut(),
+    )?;
     tx.commit()?;
+    report.committed = true;
     log::info!("transaction committed");
 
     Ok(())
 }
 
-fn lap_times(
-    race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
+fn import_season(args: &SeasonArgs, db: &mut dyn Backend) -> anyhow::Result<()> {
+    let mut report = Report::new();
+    let result = import_season_into(args, db, &mut report);
+    report.save(args.options.report.as_deref(), &result)?;
+    result
+}
+
+fn import_season_into(
+    args: &SeasonArgs,
+    db: &mut dyn Backend,
+    report: &mut Report,
 ) -> anyhow::Result<()> {
-    let file = base_path.join("lap_times.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::LapTime>() {
-        let la = r?;
-        log::info!("inserting lap analysis: {:?}", la);
-
-        let time = format!(
-            "{}:{}.{:03}",
-            la.time.num_minutes(),
-            la.time.num_seconds(),
-            la.time.num_milliseconds()
-        );
+    let season = args.dir.join(args.year.to_string());
+    let rounds = season_rounds(&season)?
+        .into_iter()
+        .filter(|(round, _)| args.from_round.is_none_or(|from| *round >= from))
+        .collect::<Vec<_>>();
+    if rounds.is_empty() {
+        anyhow::bail!("no round to import in {}", season.display());
+    }
 
-        let q = Query::insert()
-            .into_table(LapTimes::Table)
-            .columns([
-                LapTimes::RaceID,
-                LapTimes::DriverID,
-                LapTimes::Lap,
-                LapTimes::Position,
-                LapTimes::Time,
-                LapTimes::Milliseconds,
-            ])
-            .values([
-                race_id.into(),
-                la.driver_id.into(),
-                la.lap.into(),
-                la.position.into(),
-                time.into(),
-                la.time.num_milliseconds().into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        bypass_duplicates!(tx.exec_drop(q, ()))?;
+    let mut tx = db.transaction()?;
+    check_schema(&args.options, tx.as_mut())?;
+
+    for (round, path) in rounds {
+        log::info!("importing round {round} from {}", path.display());
+
+        let round_report = report.round(args.year, round);
+        import_round(
+            &args.options,
+            &path,
+            args.year,
+            round,
+            None,
+            round_report,
+            tx.as_mut(),
+        )
+        .with_context(|| {
+            if args.single_transaction {
+                format!("round {round} failed, nothing was committed")
+            } else {
+                format!("round {round} failed, resume with --from-round {round}")
+            }
+        })?;
+
+        if !args.single_transaction {
+            tx.commit()?;
+            round_report.committed = true;
+            log::info!("round {round} committed");
+            tx = db.transaction()?;
+        }
     }
 
-    log::info!("lap_times inserted");
+    tx.commit()?;
+    report
+        .rounds
+        .iter_mut()
+        .for_each(|round| round.committed = true);
+    log::info!("season {} imported", args.year);
+
     Ok(())
 }
 
-fn pit_stops(
-    race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
-) -> anyhow::Result<()> {
-    let file = base_path.join("pit_stops.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::PitStop>() {
-        let ps = r?;
-        log::info!("inserting pit stop: {:?}", ps);
+/// The round subfolders of a season folder, in round order.
+fn season_rounds(season: &Path) -> anyhow::Result<Vec<(u16, PathBuf)>> {
+    let mut rounds = Vec::new();
+    for entry in
+        std::fs::read_dir(season).map_err(|e| anyhow::anyhow!("{}: {e}", season.display()))?
+    {
+        let path = entry?.path();
+        let round = path
+            .file_name()
+            .and_then(|name| name.to_str()?.parse().ok());
+        match round {
+            Some(round) if path.is_dir() => rounds.push((round, path)),
+            _ => log::warn!("{} is not a round folder, skipped", path.display()),
+        }
+    }
+    rounds.sort();
 
-        let duration = format!(
-            "{}.{:03}",
-            ps.duration.num_seconds(),
-            ps.duration.num_milliseconds()
-        );
+    Ok(rounds)
+}
 
-        let q = Query::insert()
-            .into_table(PitStops::Table)
-            .columns([
-                PitStops::RaceID,
-                PitStops::DriverID,
-                PitStops::Stop,
-                PitStops::Lap,
-                PitStops::Time,
-                PitStops::Duration,
-                PitStops::Milliseconds,
-            ])
-            .values([
-                race_id.into(),
-                ps.driver_id.into(),
-                ps.stop.into(),
-                ps.lap.into(),
-                ps.time.format("%H:%M:%S").to_string().into(),
-                duration.into(),
-                ps.duration.num_milliseconds().into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+fn check_schema(options: &ImportOptions, tx: &mut dyn Transaction) -> anyhow::Result<()> {
+    if options.no_schema_check {
+        log::warn!("skipping the database schema check");
+        Ok(())
+    } else {
+        schema::check(tx)
     }
-
-    log::info!("pit_stops inserted");
-    Ok(())
 }
 
-fn qualifying_results(
-    race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
+/// Imports the input files of `base_path` and records the run.
+fn import_round(
+    options: &ImportOptions,
+    base_path: &Path,
+    year: i32,
+    round: u16,
+    is_sprint: Option<bool>,
+    report: &mut report::Round,
+    tx: &mut dyn Transaction,
 ) -> anyhow::Result<()> {
-    let file = base_path.join("qualifying.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::Qualifying>() {
-        let qo = r?;
-        log::info!("qualification order: {:?}", qo);
-
-        // TODO: Handle status
-
-        let q = Query::insert()
-            .into_table(Qualifying::Table)
-            .columns([
-                Qualifying::RaceID,
-                Qualifying::DriverID,
-                Qualifying::ConstructorID,
-                Qualifying::Number,
-                Qualifying::Position,
-                Qualifying::Q1,
-                Qualifying::Q2,
-                Qualifying::Q3,
-            ])
-            .values([
-                race_id.into(),
-                qo.driver_id.into(),
-                qo.constructor_id.into(),
-                qo.number.into(),
-                qo.position.into(),
-                qo.q1.into(),
-                qo.q2.into(),
-                qo.q3.into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+    let started = Instant::now();
+    let input = Input::new(base_path, options.format);
+    let race_id = race_id(tx, year, round)?;
+    report.race_id = Some(race_id);
+    let is_sprint = sprint_weekend(race_id, &input, is_sprint, tx)?;
+    report.sprint = Some(is_sprint);
+    let mut resolver = Resolver::new(race_id, year, &input);
+    let mut run = Run::new(race_id, round, year, is_sprint);
+
+    // Files changing how the rows are resolved and checked, the lap floors
+    // are only read as csv
+    let csv = Input::new(base_path, Some(Format::Csv));
+    for (name, input) in [
+        ("driver_numbers", &input),
+        ("constructor_aliases", &input),
+        ("lap_floors", &csv),
+    ] {
+        if input.exists(name) {
+            run.input_hashes.insert(name, input.hash(name)?);
+        }
     }
 
-    log::info!("qualifying_results inserted");
-    Ok(())
-}
+    for table in options.tables(&input, is_sprint) {
+        log::debug!("importing {}", table.name);
+        let table_started = Instant::now();
+        if !run.input_hashes.contains_key(table.file) {
+            run.input_hashes.insert(table.file, input.hash(table.file)?);
+        }
+        let counts = (table.import)(race_id, &input, &mut resolver, &mut run.journal, tx)?;
+        let duration_ms = report::millis(table_started.elapsed());
+        log::info!(
+            "{}: {} inserted, {} updated, {} skipped in {duration_ms} ms",
+            table.name,
+            counts.inserted,
+            counts.updated,
+            counts.skipped
+        );
+        if counts.written() > 0 {
+            resolver.entries_changed();
+        }
+        run.row_counts.insert(table.name, counts.written());
+        report.tables.insert(
+            table.name,
+            report::Table {
+                counts,
+                duration_ms,
+            },
+        );
+    }
 
-fn results(race_id: i32, base_path: &std::path::Path, tx: &mut Transaction) -> anyhow::Result<()> {
-    let file = base_path.join("results.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::RaceResult>() {
-        let rr = r?;
-        log::info!("inserting driver race result: {:?}", rr);
-
-        // TODO: Handle status
-
-        let q = Query::insert()
-            .into_table(Results::Table)
-            .columns([
-                Results::RaceID,
-                Results::DriverID,
-                Results::ConstructorID,
-                Results::Number,
-                Results::Grid,
-                Results::Position,
-                Results::PositionText,
-                Results::PositionOrder,
-                Results::Points,
-                Results::Laps,
-                Results::Time,
-                Results::Milliseconds,
-                Results::FastestLap,
-                Results::Rank,
-                Results::FastestLapTime,
-                Results::FastestLapSpeed,
-            ])
-            .values([
-                race_id.into(),
-                rr.driver_id.into(),
-                rr.constructor_id.into(),
-                rr.driver_number.into(),
-                rr.grid.into(),
-                rr.position.into(),
-                rr.position_text.into(),
-                rr.position_order.into(),
-                rr.points.into(),
-                rr.laps.into(),
-                rr.time.into(),
-                rr.milliseconds.into(),
-                rr.fastest_lap.into(),
-                rr.rank.into(),
-                rr.fatest_lap_time.into(),
-                rr.fastest_lap_speed.into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+    if options.no_checks {
+        log::warn!("skipping the consistency checks");
+    } else {
+        report.findings = checks::run(
+            race_id,
+            base_path,
+            options.strictness,
+            options.check_slow_laps,
+            tx,
+        )?;
+        checks::report(&report.findings)?;
     }
 
-    log::info!("driver race results inserted");
+    report.run_id = Some(run.record(tx)?);
+    report.duration_ms = report::millis(started.elapsed());
     Ok(())
 }
 
-fn constructor_results(
-    race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
-) -> anyhow::Result<()> {
-    let file = base_path.join("constructor_results.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::ConstructorResult>() {
-        let cr = r?;
-        log::info!("inserting constructor result: {:?}", cr);
-
-        let q = Query::insert()
-            .into_table(ConstructorResults::Table)
-            .columns([
-                ConstructorResults::RaceID,
-                ConstructorResults::ConstructorID,
-                ConstructorResults::Points,
-            ])
-            .values([race_id.into(), cr.constructor_id.into(), cr.points.into()])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+fn diff(args: &DiffArgs, db: &mut dyn Backend) -> anyhow::Result<()> {
+    let base_path = base_path();
+    let input = Input::new(&base_path, args.import.options.format);
+    let round = args.import.round.expect("round is required");
+    let year = chrono::Utc::now().year();
+
+    // Nothing is written, the transaction is dropped without a commit
+    let mut tx = db.transaction()?;
+    check_schema(&args.import.options, tx.as_mut())?;
+
+    let race_id = race_id(tx.as_mut(), year, round)?;
+    let is_sprint = sprint_weekend(race_id, &input, args.import.is_sprint, tx.as_mut())?;
+    let mut resolver = Resolver::new(race_id, year, &input);
+
+    let mut diffs = Vec::new();
+    for table in args.import.options.tables(&input, is_sprint) {
+        if let Some(diff) = table.diff {
+            diffs.push(diff(race_id, &input, &mut resolver, tx.as_mut())?);
+        }
+    }
+
+    match args.output {
+        Output::Text => diffs.iter().for_each(|diff| print!("{diff}")),
+        Output::Json => println!("{}", serde_json::to_string_pretty(&diffs)?),
     }
 
-    log::info!("constructor race results inserted");
     Ok(())
 }
 
-fn driver_standings(
-    race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
-) -> anyhow::Result<()> {
-    let file = base_path.join("driver_standings.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::DriverStanding>() {
-        let dd = r?;
-        log::info!("inserting driver championship: {:?}", dd);
-
-        let q = Query::insert()
-            .into_table(DriverStandings::Table)
-            .columns([
-                DriverStandings::RaceID,
-                DriverStandings::DriverID,
-                DriverStandings::Points,
-                DriverStandings::Position,
-                DriverStandings::PositionText,
-                DriverStandings::Wins,
-            ])
-            .values([
-                race_id.into(),
-                dd.driver_id.into(),
-                dd.points.into(),
-                dd.position.into(),
-                dd.position_text.into(),
-                dd.wins.into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+fn export(year: i32, round: u16, out: &Path, db: &mut dyn Backend) -> anyhow::Result<()> {
+    std::fs::create_dir_all(out)?;
+
+    let mut tx = db.transaction()?;
+    let race_id = race_id(tx.as_mut(), year, round)?;
+    for table in importers::TABLES {
+        if let Some(export) = table.export {
+            export(race_id, out, tx.as_mut())?;
+        }
     }
 
-    log::info!("driver championship inserted");
     Ok(())
 }
 
-fn constructor_standings(
+/// Folder of the input files.
+fn base_path() -> PathBuf {
+    env::var("F1_SQL_UPDATER_CSV_FOLDER")
+        .unwrap_or("csv".into())
+        .into()
+}
+
+fn race_id(tx: &mut dyn Transaction, year: i32, round: u16) -> anyhow::Result<i32> {
+    tx.query(
+        &Query::select()
+            .column(Races::RaceID)
+            .from(Races::Table)
+            .and_where(Expr::col(Races::Round).eq(round))
+            .and_where(Expr::col(Races::Year).eq(year))
+            .to_owned(),
+    )?
+    .first()
+    .ok_or_else(|| anyhow::anyhow!("race not found for round {round} of {year}"))?
+    .get(0)
+}
+
+/// Whether the race is a sprint weekend, detected from `races.sprint_date` or
+/// from the sprint results shipped with the input files.
+///
+/// A flag given on the command line wins, with a warning when the detection
+/// disagrees.
+fn sprint_weekend(
     race_id: i32,
-    base_path: &std::path::Path,
-    tx: &mut Transaction,
-) -> anyhow::Result<()> {
-    let file = base_path.join("constructor_standings.csv");
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::ConstructorStanding>() {
-        let cc = r?;
-        log::info!("inserting constructor championship: {:?}", cc);
-
-        let q = Query::insert()
-            .into_table(ConstructorStandings::Table)
-            .columns([
-                ConstructorStandings::RaceID,
-                ConstructorStandings::ConstructorID,
-                ConstructorStandings::Points,
-                ConstructorStandings::Position,
-                ConstructorStandings::PositionText,
-                ConstructorStandings::Wins,
-            ])
-            .values([
-                race_id.into(),
-                cc.constructor_id.into(),
-                cc.points.into(),
-                cc.position.into(),
-                cc.position.into(),
-                cc.wins.into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+    input: &Input,
+    flag: Option<bool>,
+    tx: &mut dyn Transaction,
+) -> anyhow::Result<bool> {
+    let scheduled = tx
+        .query(
+            &Query::select()
+                .column(Races::SprintDate)
+                .from(Races::Table)
+                .and_where(Expr::col(Races::RaceID).eq(race_id))
+                .to_owned(),
+        )?
+        .first()
+        .map(|row| row.get_opt::<String>(0))
+        .transpose()?
+        .flatten()
+        .is_some();
+    let detected = scheduled || input.exists("sprint_results");
+
+    match flag {
+        Some(true) if !detected => log::warn!(
+            "round given as a sprint weekend but the race has no sprint_date and there is no \
+             sprint_results file"
+        ),
+        Some(false) if detected => log::warn!(
+            "round given as a regular weekend but the race has a sprint_date or a \
+             sprint_results file, the sprint tables are skipped"
+        ),
+        Some(_) => {}
+        None => log::info!(
+            "{} weekend detected",
+            if detected { "sprint" } else { "regular" }
+        ),
     }
 
-    log::info!("constructor championship inserted");
-    Ok(())
+    Ok(flag.unwrap_or(detected))
 }
 
-fn sprint_lap_times(race_id: i32, tx: &mut Transaction) -> anyhow::Result<()> {
-    let file = "/etc/csv/sprint_laps_analysis.csv";
-    let mut rdr = csv::Reader::from_path(file)?;
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::journal::Target;
+    use crate::testing;
+
+    const ROUND_TABLES: &[&str] = &[
+        "lapTimes",
+        "qualifying",
+        "results",
+        "driverStandings",
+        "constructorStandings",
+        "constructorResults",
+        "sprintResults",
+    ];
+
+    /// Rows of each table of the round.
+    fn rows(tx: &mut dyn Transaction) -> Vec<(&'static str, i32)> {
+        ROUND_TABLES
+            .iter()
+            .map(|table| {
+                let sql = format!(
+                    "SELECT COUNT(*) FROM {table} WHERE raceId = {}",
+                    testing::RACE
+                );
+                (*table, tx.query_sql(&sql).unwrap()[0].get(0).unwrap())
+            })
+            .collect()
+    }
 
-    for r in rdr.deserialize::<models::LapTime>() {
-        let la = r?;
-        log::info!("inserting sprint lap time: {:?}", la);
+    fn diffs(files: &testing::Folder, tx: &mut dyn Transaction) -> Vec<diff::TableDiff> {
+        let input = Input::new(&files.0, None);
+        let mut resolver = Resolver::new(testing::RACE, 2026, &input);
+        testing::options()
+            .tables(&input, true)
+            .filter_map(|table| table.diff)
+            .map(|diff| diff(testing::RACE, &input, &mut resolver, tx).unwrap())
+            .collect()
+    }
 
-        let time = format!(
-            "{}:{}.{:3}",
-            la.time.num_minutes(),
-            la.time.num_seconds(),
-            la.time.num_milliseconds()
+    #[test]
+    fn sprint_round_imports_diffs_and_rolls_back() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+
+        let mut tx = db.transaction().unwrap();
+        let round = testing::import(&files, tx.as_mut()).unwrap();
+        assert!(round.findings.is_empty(), "{:?}", round.findings);
+        tx.commit().unwrap();
+
+        let mut tx = db.transaction().unwrap();
+        assert_eq!(
+            rows(tx.as_mut()),
+            [
+                ("lapTimes", 6),
+                ("qualifying", 2),
+                ("results", 2),
+                ("driverStandings", 2),
+                ("constructorStandings", 2),
+                ("constructorResults", 2),
+                ("sprintResults", 2)
+            ]
         );
-
-        let q = Query::insert()
-            .into_table(LapTimes::Table)
-            .columns([
-                LapTimes::RaceID,
-                LapTimes::DriverID,
-                LapTimes::Lap,
-                LapTimes::Position,
-                LapTimes::Time,
-                LapTimes::Milliseconds,
-            ])
-            .values([
-                race_id.into(),
-                la.driver_id.into(),
-                la.lap.into(),
-                la.position.into(),
-                time.into(),
-                la.time.num_milliseconds().into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+        for diff in diffs(&files, tx.as_mut()) {
+            assert!(
+                diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty(),
+                "{diff}"
+            );
+        }
+        drop(tx);
+
+        journal::rollback(
+            db.as_mut(),
+            Target::Race {
+                year: 2026,
+                round: 5,
+            },
+        )
+        .unwrap();
+
+        let mut tx = db.transaction().unwrap();
+        assert!(rows(tx.as_mut()).iter().all(|(_, count)| *count == 0));
+        for diff in diffs(&files, tx.as_mut()) {
+            assert!(!diff.added.is_empty() && diff.removed.is_empty(), "{diff}");
+        }
+        // Round 4 is left alone
+        let standings = tx
+            .query_sql("SELECT COUNT(*) FROM driverStandings WHERE raceId = 1")
+            .unwrap();
+        assert_eq!(standings[0].get::<i32>(0).unwrap(), 2);
+
+        drop(tx);
+        // Nothing left to roll back
+        assert!(journal::rollback(
+            db.as_mut(),
+            Target::Race {
+                year: 2026,
+                round: 5
+            }
+        )
+        .is_err());
     }
 
-    log::info!("sprint lap times inserted");
-    Ok(())
-}
+    #[test]
+    fn run_hashes_the_mapping_files() {
+        let mut db = testing::database();
+        let files = testing::round_files();
+        std::fs::write(
+            files.0.join("driver_numbers.csv"),
+            "year,number,driver_id\n2026,1,830\n",
+        )
+        .unwrap();
+        std::fs::write(
+            files.0.join("lap_floors.csv"),
+            "circuit_ref,min_lap_time\nmiami,1:20.000\n",
+        )
+        .unwrap();
+
+        let mut tx = db.transaction().unwrap();
+        testing::import(&files, tx.as_mut()).unwrap();
+        let runs = tx.query_sql("SELECT inputHashes FROM updaterRuns").unwrap();
+        let hashes: BTreeMap<String, String> =
+            serde_json::from_str(&runs[0].get::<String>(0).unwrap()).unwrap();
+
+        let input = Input::new(&files.0, None);
+        assert_eq!(
+            hashes["driver_numbers"],
+            input.hash("driver_numbers").unwrap()
+        );
+        assert_eq!(hashes["lap_floors"], input.hash("lap_floors").unwrap());
+        assert!(hashes.contains_key("results") && !hashes.contains_key("constructor_aliases"));
+    }
 
-fn driver_sprint_results(race_id: i32, tx: &mut Transaction) -> anyhow::Result<()> {
-    let file = "/etc/csv/driver_sprint_result.csv";
-    let mut rdr = csv::Reader::from_path(file)?;
-
-    for r in rdr.deserialize::<models::DriverSprintResult>() {
-        let dsr = r?;
-        log::info!("inserting driver sprint result: {:?}", dsr);
-
-        let driver_id = *tx
-            .query_map(
-                format!(
-                    "SELECT driverId FROM drivers WHERE number = {}",
-                    driver_number(dsr.no)
-                ),
-                |driver_id: i32| driver_id,
-            )?
-            .first()
-            .expect("driver not found");
-        let constructor_id = *tx
-            .query_map(
-                format!(
-                    "SELECT constructorId FROM constructors WHERE name = '{}'",
-                    dsr.entrant
-                ),
-                |constructor_id: i32| constructor_id,
-            )?
-            .first()
-            .expect("constructor not found");
-
-        // TODO: Handle status
-
-        let q = Query::insert()
-            .into_table(SprintResults::Table)
-            .columns([
-                SprintResults::RaceID,
-                SprintResults::DriverID,
-                SprintResults::ConstructorID,
-                SprintResults::Number,
-                SprintResults::Grid,
-                SprintResults::Position,
-                SprintResults::PositionText,
-                SprintResults::PositionOrder,
-                SprintResults::Points,
-                SprintResults::Laps,
-                SprintResults::Time,
-                SprintResults::Milliseconds,
-                SprintResults::FastestLap,
-                SprintResults::FastestLapTime,
-            ])
-            .values([
-                race_id.into(),
-                driver_id.into(),
-                constructor_id.into(),
-                dsr.no.into(),
-                dsr.grid.into(),
-                dsr.position.parse::<u16>().ok().into(),
-                dsr.position.into(),
-                dsr.position_order.into(),
-                dsr.points.into(),
-                dsr.laps.into(),
-                dsr.time.into(),
-                dsr.milliseconds.into(),
-                dsr.fastest_lap.into(),
-                dsr.fatest_lap_time.into(),
-            ])?
-            .to_string(MysqlQueryBuilder);
-
-        tx.exec_drop(q, ())?;
+    /// A `<dir>/2026` season folder holding the files of round 5 in `05`, and
+    /// an empty folder for each of the other `rounds`.
+    fn season(rounds: &[&str]) -> testing::Folder {
+        let dir = testing::folder();
+        let season = dir.0.join("2026");
+        let files = testing::round_files();
+        std::fs::create_dir_all(season.join("05")).unwrap();
+        for entry in std::fs::read_dir(&files.0).unwrap() {
+            let path = entry.unwrap().path();
+            std::fs::copy(&path, season.join("05").join(path.file_name().unwrap())).unwrap();
+        }
+        for round in rounds {
+            std::fs::create_dir_all(season.join(round)).unwrap();
+        }
+
+        dir
     }
 
-    log::info!("driver sprint results inserted");
-    Ok(())
-}
+    fn season_args(dir: &testing::Folder) -> SeasonArgs {
+        SeasonArgs {
+            year: 2026,
+            dir: dir.0.clone(),
+            single_transaction: false,
+            from_round: None,
+            options: testing::options(),
+        }
+    }
 
-fn constructor_sprint_results(race_id: i32, tx: &mut Transaction) -> anyhow::Result<()> {
-    let file = "/etc/csv/constructor_race_result.csv";
-    let mut rdr = csv::Reader::from_path(file)?;
+    /// Round 6 of 2026, without input files in the season folders.
+    fn add_round_six(db: &mut dyn Backend) {
+        let mut tx = db.transaction().unwrap();
+        tx.exec_sql(
+            "INSERT INTO races (raceId, year, round, circuitId, name, date, url) VALUES \
+             (3, 2026, 6, 1, 'Monaco Grand Prix', '2026-06-07', 'u')",
+        )
+        .unwrap();
+        tx.commit().unwrap();
+    }
 
-    let driver_sprint_file = "/etc/csv/driver_sprint_result.csv";
-    let mut driver_sprint_rdr = csv::Reader::from_path(driver_sprint_file)?;
+    fn round_five_results(db: &mut dyn Backend) -> i32 {
+        let mut tx = db.transaction().unwrap();
+        let rows = tx
+            .query_sql("SELECT COUNT(*) FROM results WHERE raceId = 2")
+            .unwrap();
+        rows[0].get(0).unwrap()
+    }
 
-    let drivers = driver_sprint_rdr
-        .deserialize::<models::DriverSprintResult>()
-        .collect::<Result<Vec<_>, _>>()?;
+    #[test]
+    fn season_rounds_are_sorted_by_number() {
+        let dir = season(&["10", "4"]);
+        std::fs::write(dir.0.join("2026").join("notes.txt"), "").unwrap();
 
-    for r in rdr.deserialize::<models::ConstructorResult>() {
-        let cr = r?;
-        log::info!("inserting constructor sprint result: {:?}", cr);
+        let rounds = season_rounds(&dir.0.join("2026")).unwrap();
+        assert_eq!(
+            rounds.iter().map(|(round, _)| *round).collect::<Vec<_>>(),
+            [4, 5, 10]
+        );
+    }
 
-        let q = Query::insert()
-            .into_table(ConstructorResults::Table)
-            .columns([
-                ConstructorResults::RaceID,
-                ConstructorResults::ConstructorID,
-                ConstructorResults::Points,
-            ])
-            .values([race_id.into(), cr.constructor_id.into(), cr.points.into()])?
-            .to_string(MysqlQueryBuilder);
+    #[test]
+    fn season_resumes_from_a_round() {
+        let mut db = testing::database();
+        // Round 4 has no files, importing it would fail
+        let dir = season(&["04"]);
+        let args = SeasonArgs {
+            from_round: Some(5),
+            ..season_args(&dir)
+        };
+
+        let mut report = Report::new();
+        import_season_into(&args, db.as_mut(), &mut report).unwrap();
+        assert_eq!(report.rounds.len(), 1);
+        assert!(report.rounds[0].round == 5 && report.rounds[0].committed);
+        assert_eq!(round_five_results(db.as_mut()), 2);
+    }
 
-        tx.exec_drop(q, ())?;
+    #[test]
+    fn season_commits_each_round() {
+        let mut db = testing::database();
+        add_round_six(db.as_mut());
+        let dir = season(&["06"]);
+
+        let mut report = Report::new();
+        let error = import_season_into(&season_args(&dir), db.as_mut(), &mut report).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "round 6 failed, resume with --from-round 6"
+        );
+        assert!(report.rounds[0].committed && !report.rounds[1].committed);
+        assert_eq!(round_five_results(db.as_mut()), 2);
     }
 
-    log::info!("constructor sprint result inserted");
-    Ok(())
-}
+    #[test]
+    fn season_in_a_single_transaction_commits_nothing_on_failure() {
+        let mut db = testing::database();
+        add_round_six(db.as_mut());
+        let dir = season(&["06"]);
+        let args = SeasonArgs {
+            single_transaction: true,
+            ..season_args(&dir)
+        };
+
+        let mut report = Report::new();
+        let error = import_season_into(&args, db.as_mut(), &mut report).unwrap_err();
+        assert_eq!(error.to_string(), "round 6 failed, nothing was committed");
+        assert!(report.rounds.iter().all(|round| !round.committed));
+        assert_eq!(round_five_results(db.as_mut()), 0);
+    }
 
-fn driver_number(no: u16) -> u16 {
-    if no == 1 {
-        33
-    } else {
-        no
+    #[test]
+    fn season_without_rounds_names_its_folder() {
+        let mut db = testing::database();
+        let dir = season(&[]);
+        let args = SeasonArgs {
+            from_round: Some(6),
+            ..season_args(&dir)
+        };
+
+        let error = import_season_into(&args, db.as_mut(), &mut Report::new()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("no round to import in {}", dir.0.join("2026").display())
+        );
     }
 }
@@ -0,0 +1,278 @@
+use sea_query::{
+    Alias, ColumnDef, Expr, ForeignKey, Func, Iden, Query, Table, TableCreateStatement,
+};
+
+use crate::backend::{Backend, Transaction};
+use crate::schema;
+use crate::tables::{Races, UpdaterJournal, UpdaterMigrations, UpdaterRuns};
+
+mod f1db;
+
+/// A schema change shipped with the binary, applied once by `migrate`.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: MigrateFn,
+    pub down: MigrateFn,
+}
+
+type MigrateFn = fn(&mut dyn Transaction) -> anyhow::Result<()>;
+
+/// Every migration, in the order they are applied.
+///
+/// Versions are consecutive and never reused, a released migration is not
+/// edited, a new one is appended instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "f1db tables",
+        up: |tx| schema::create(tx, f1db::tables()),
+        // Baseline of the f1db data, never dropped
+        down: |_| Ok(()),
+    },
+    Migration {
+        version: 2,
+        name: "updater runs",
+        up: |tx| schema::create(tx, runs()),
+        down: |tx| schema::drop(tx, runs()),
+    },
+    Migration {
+        version: 3,
+        name: "run journal",
+        up: |tx| {
+            schema::create(tx, journal())?;
+            tx.exec_schema(
+                Table::alter()
+                    .table(UpdaterRuns::Table)
+                    .add_column(ColumnDef::new(UpdaterRuns::RolledBackAt).date_time()),
+            )
+        },
+        down: |tx| {
+            tx.exec_schema(
+                Table::alter()
+                    .table(UpdaterRuns::Table)
+                    .drop_column(UpdaterRuns::RolledBackAt),
+            )?;
+            schema::drop(tx, journal())
+        },
+    },
+    Migration {
+        version: 4,
+        name: "race info",
+        up: |tx| {
+            // Created with the table by `init-schema` and the first migration
+            // of earlier builds
+            for column in race_info() {
+                if !schema::column_exists(tx, &Races::Table.to_string(), &column.get_column_name())?
+                {
+                    tx.exec_schema(
+                        Table::alter()
+                            .table(Races::Table)
+                            .add_column(&mut column.clone()),
+                    )?;
+                }
+            }
+            Ok(())
+        },
+        down: |tx| {
+            for column in race_info() {
+                tx.exec_schema(
+                    Table::alter()
+                        .table(Races::Table)
+                        .drop_column(Alias::new(column.get_column_name())),
+                )?;
+            }
+            Ok(())
+        },
+    },
+];
+
+/// The version of the schema this binary was built for.
+pub const LATEST: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Version of the database, 0 when it was never migrated.
+pub fn version(tx: &mut dyn Transaction) -> anyhow::Result<u32> {
+    if !schema::table_exists(tx, &UpdaterMigrations::Table.to_string())? {
+        return Ok(0);
+    }
+
+    let rows = tx.query(
+        &Query::select()
+            .expr(Func::max(Expr::col(UpdaterMigrations::Version)))
+            .from(UpdaterMigrations::Table)
+            .to_owned(),
+    )?;
+
+    Ok(rows
+        .first()
+        .map(|row| row.get_opt(0))
+        .transpose()?
+        .flatten()
+        .unwrap_or(0))
+}
+
+/// Applies or reverts migrations until the database is at the `target` version.
+///
+/// Each migration runs in its own transaction together with the update of the
+/// version table. MySQL commits schema statements right away though, a failed
+/// migration may have to be cleaned up by hand there.
+pub fn migrate(db: &mut dyn Backend, target: Option<u32>) -> anyhow::Result<()> {
+    let target = target.unwrap_or(LATEST);
+    if target > LATEST {
+        anyhow::bail!("unknown version {target}, the latest is {LATEST}");
+    }
+
+    let mut tx = db.transaction()?;
+    tx.exec_schema(
+        Table::create()
+            .table(UpdaterMigrations::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(UpdaterMigrations::Version)
+                    .integer()
+                    .not_null()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(UpdaterMigrations::Name)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(UpdaterMigrations::AppliedAt)
+                    .date_time()
+                    .not_null(),
+            ),
+    )?;
+    let current = version(tx.as_mut())?;
+    tx.commit()?;
+
+    if current > LATEST {
+        anyhow::bail!("database at version {current} is newer than this binary ({LATEST})");
+    }
+    if current == target {
+        log::info!("database already at version {current}");
+        return Ok(());
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current && m.version <= target)
+    {
+        log::info!(
+            "applying migration {} ({})",
+            migration.version,
+            migration.name
+        );
+        let mut tx = db.transaction()?;
+        (migration.up)(tx.as_mut())?;
+        tx.exec(
+            Query::insert()
+                .into_table(UpdaterMigrations::Table)
+                .columns([
+                    UpdaterMigrations::Version,
+                    UpdaterMigrations::Name,
+                    UpdaterMigrations::AppliedAt,
+                ])
+                .values_panic([
+                    migration.version.into(),
+                    migration.name.into(),
+                    chrono::Utc::now()
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                        .into(),
+                ]),
+        )?;
+        tx.commit()?;
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|m| m.version > target && m.version <= current)
+    {
+        log::info!(
+            "reverting migration {} ({})",
+            migration.version,
+            migration.name
+        );
+        let mut tx = db.transaction()?;
+        (migration.down)(tx.as_mut())?;
+        tx.exec(
+            Query::delete()
+                .from_table(UpdaterMigrations::Table)
+                .and_where(Expr::col(UpdaterMigrations::Version).eq(migration.version)),
+        )?;
+        tx.commit()?;
+    }
+
+    log::info!("database migrated to version {target}");
+    Ok(())
+}
+
+fn runs() -> Vec<TableCreateStatement> {
+    vec![Table::create()
+        .table(UpdaterRuns::Table)
+        .if_not_exists()
+        .col(
+            ColumnDef::new(UpdaterRuns::RunID)
+                .integer()
+                .not_null()
+                .auto_increment()
+                .primary_key(),
+        )
+        .col(ColumnDef::new(UpdaterRuns::RaceID).integer().not_null())
+        .col(ColumnDef::new(UpdaterRuns::Round).integer().not_null())
+        .col(ColumnDef::new(UpdaterRuns::Year).integer().not_null())
+        .col(ColumnDef::new(UpdaterRuns::Sprint).boolean().not_null())
+        .col(ColumnDef::new(UpdaterRuns::RowCounts).text().not_null())
+        .col(ColumnDef::new(UpdaterRuns::InputHashes).text().not_null())
+        .col(
+            ColumnDef::new(UpdaterRuns::Version)
+                .string_len(32)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(UpdaterRuns::CreatedAt)
+                .date_time()
+                .not_null(),
+        )
+        .foreign_key(&mut schema::race(UpdaterRuns::Table, UpdaterRuns::RaceID))
+        .to_owned()]
+}
+
+fn journal() -> Vec<TableCreateStatement> {
+    vec![Table::create()
+        .table(UpdaterJournal::Table)
+        .if_not_exists()
+        .col(
+            ColumnDef::new(UpdaterJournal::EntryID)
+                .integer()
+                .not_null()
+                .auto_increment()
+                .primary_key(),
+        )
+        .col(ColumnDef::new(UpdaterJournal::RunID).integer().not_null())
+        .col(
+            ColumnDef::new(UpdaterJournal::TableName)
+                .string_len(64)
+                .not_null(),
+        )
+        .col(ColumnDef::new(UpdaterJournal::RowKey).text().not_null())
+        .col(ColumnDef::new(UpdaterJournal::OldValues).text())
+        .foreign_key(
+            ForeignKey::create()
+                .from(UpdaterJournal::Table, UpdaterJournal::RunID)
+                .to(UpdaterRuns::Table, UpdaterRuns::RunID),
+        )
+        .to_owned()]
+}
+
+fn race_info() -> Vec<ColumnDef> {
+    vec![
+        ColumnDef::new(Races::ScheduledLaps).integer().to_owned(),
+        ColumnDef::new(Races::CompletedLaps).integer().to_owned(),
+        ColumnDef::new(Races::RedFlagged).boolean().to_owned(),
+        ColumnDef::new(Races::Shortened).boolean().to_owned(),
+    ]
+}
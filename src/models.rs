@@ -1,36 +1,65 @@
 use serde::de::Error;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Deserialize, Debug)]
+/// Any of the keys a csv can use to identify a driver.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DriverKey {
+    Id(i32),
+    Ref(String),
+    Code(String),
+    /// Race number, resolved against the imported season.
+    Number(u16),
+}
+
+/// Any of the keys a csv can use to identify a constructor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConstructorKey {
+    Id(i32),
+    Ref(String),
+    Name(String),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct LapTime {
-    pub driver_id: i32,
+    pub driver_id: Option<i32>,
+    pub driver_ref: Option<String>,
+    pub code: Option<String>,
+    pub number: Option<u16>,
     pub lap: u16,
     pub position: u16,
-    #[serde(deserialize_with = "de_time")]
+    #[serde(deserialize_with = "de_time", serialize_with = "ser_time")]
     pub time: chrono::TimeDelta,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Qualifying {
-    pub driver_id: i32,
-    pub constructor_id: i32,
+    pub driver_id: Option<i32>,
+    pub driver_ref: Option<String>,
+    pub code: Option<String>,
+    pub constructor_id: Option<i32>,
+    pub constructor_ref: Option<String>,
     pub position: u16,
     pub number: u16,
-    pub q1: String,
+    pub q1: Option<String>,
     pub q2: Option<String>,
     pub q3: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct RaceResult {
-    pub driver_id: i32,
-    pub constructor_id: i32,
+    pub driver_id: Option<i32>,
+    pub driver_ref: Option<String>,
+    pub code: Option<String>,
+    pub constructor_id: Option<i32>,
+    pub constructor_ref: Option<String>,
     pub driver_number: u16,
-    pub position: u16,
+    /// Empty for the drivers not classified
+    pub position: Option<u16>,
     pub grid: u16,
     pub position_text: String,
     pub position_order: u16,
-    pub points: u16,
+    #[serde(serialize_with = "ser_points")]
+    pub points: f32,
     pub laps: u16,
     pub time: Option<String>,
     pub milliseconds: Option<String>,
@@ -40,31 +69,39 @@ pub struct RaceResult {
     pub fastest_lap_speed: Option<f32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct DriverStanding {
-    pub driver_id: i32,
-    pub points: u32,
+    pub driver_id: Option<i32>,
+    pub driver_ref: Option<String>,
+    pub code: Option<String>,
+    pub number: Option<u16>,
+    #[serde(serialize_with = "ser_points")]
+    pub points: f32,
     pub position: u32,
     pub position_text: String,
     pub wins: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ConstructorStanding {
-    pub constructor_id: i32,
-    pub points: u32,
+    pub constructor_id: Option<i32>,
+    pub constructor_ref: Option<String>,
+    #[serde(serialize_with = "ser_points")]
+    pub points: f32,
     pub position: u32,
     pub position_text: String,
     pub wins: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct ConstructorResult {
-    pub constructor_id: i32,
-    pub points: u16,
+    pub constructor_id: Option<i32>,
+    pub constructor_ref: Option<String>,
+    #[serde(serialize_with = "ser_points")]
+    pub points: f32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct DriverSprintResult {
     pub no: u16,
     pub entrant: String,
@@ -72,7 +109,8 @@ pub struct DriverSprintResult {
     pub position: String,
     #[serde(rename = "positionOrder")]
     pub position_order: u16,
-    pub points: u16,
+    #[serde(serialize_with = "ser_points")]
+    pub points: f32,
     pub laps: u16,
     pub time: Option<String>,
     pub milliseconds: Option<String>,
@@ -84,17 +122,145 @@ pub struct DriverSprintResult {
     pub fastest_lap_speed: Option<f32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct PitStop {
-    pub driver_id: i32,
+    pub driver_id: Option<i32>,
+    pub driver_ref: Option<String>,
+    pub code: Option<String>,
+    pub number: Option<u16>,
     pub stop: u16,
     pub lap: u16,
-    #[serde(deserialize_with = "de_local_time")]
+    #[serde(deserialize_with = "de_local_time", serialize_with = "ser_local_time")]
     pub time: chrono::NaiveTime,
-    #[serde(deserialize_with = "de_pit_stop_duration")]
+    #[serde(
+        deserialize_with = "de_pit_stop_duration",
+        serialize_with = "ser_pit_stop_duration"
+    )]
     pub duration: chrono::TimeDelta,
 }
 
+/// What happened to the race weekend, all the fields are optional and only
+/// the given ones are written to `races`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RaceInfo {
+    /// Actual date and start time of the race, in UTC
+    pub date: Option<String>,
+    pub time: Option<String>,
+    pub fp1_date: Option<String>,
+    pub fp1_time: Option<String>,
+    pub fp2_date: Option<String>,
+    pub fp2_time: Option<String>,
+    pub fp3_date: Option<String>,
+    pub fp3_time: Option<String>,
+    pub quali_date: Option<String>,
+    pub quali_time: Option<String>,
+    pub sprint_date: Option<String>,
+    pub sprint_time: Option<String>,
+    pub scheduled_laps: Option<u16>,
+    pub completed_laps: Option<u16>,
+    pub red_flagged: Option<bool>,
+}
+
+/// Fastest plausible lap of a circuit, read from `lap_floors.csv`.
+#[derive(Deserialize, Debug)]
+pub struct LapFloor {
+    pub circuit_ref: String,
+    #[serde(deserialize_with = "de_time")]
+    pub min_lap_time: chrono::TimeDelta,
+}
+
+impl LapTime {
+    pub fn driver(&self) -> anyhow::Result<DriverKey> {
+        driver_key(self.driver_id, &self.driver_ref, &self.code, self.number)
+    }
+}
+
+impl Qualifying {
+    pub fn driver(&self) -> anyhow::Result<DriverKey> {
+        driver_key(
+            self.driver_id,
+            &self.driver_ref,
+            &self.code,
+            Some(self.number),
+        )
+    }
+
+    pub fn constructor(&self) -> anyhow::Result<ConstructorKey> {
+        constructor_key(self.constructor_id, &self.constructor_ref)
+    }
+}
+
+impl RaceResult {
+    pub fn driver(&self) -> anyhow::Result<DriverKey> {
+        driver_key(
+            self.driver_id,
+            &self.driver_ref,
+            &self.code,
+            Some(self.driver_number),
+        )
+    }
+
+    pub fn constructor(&self) -> anyhow::Result<ConstructorKey> {
+        constructor_key(self.constructor_id, &self.constructor_ref)
+    }
+}
+
+impl DriverStanding {
+    pub fn driver(&self) -> anyhow::Result<DriverKey> {
+        driver_key(self.driver_id, &self.driver_ref, &self.code, self.number)
+    }
+}
+
+impl ConstructorStanding {
+    pub fn constructor(&self) -> anyhow::Result<ConstructorKey> {
+        constructor_key(self.constructor_id, &self.constructor_ref)
+    }
+}
+
+impl ConstructorResult {
+    pub fn constructor(&self) -> anyhow::Result<ConstructorKey> {
+        constructor_key(self.constructor_id, &self.constructor_ref)
+    }
+}
+
+impl DriverSprintResult {
+    pub fn constructor(&self) -> ConstructorKey {
+        ConstructorKey::Name(self.entrant.clone())
+    }
+}
+
+impl PitStop {
+    pub fn driver(&self) -> anyhow::Result<DriverKey> {
+        driver_key(self.driver_id, &self.driver_ref, &self.code, self.number)
+    }
+}
+
+fn driver_key(
+    driver_id: Option<i32>,
+    driver_ref: &Option<String>,
+    code: &Option<String>,
+    number: Option<u16>,
+) -> anyhow::Result<DriverKey> {
+    match (driver_id, driver_ref, code, number) {
+        (Some(id), ..) => Ok(DriverKey::Id(id)),
+        (_, Some(driver_ref), ..) => Ok(DriverKey::Ref(driver_ref.clone())),
+        (_, _, Some(code), _) => Ok(DriverKey::Code(code.clone())),
+        (.., Some(number)) => Ok(DriverKey::Number(number)),
+        _ => anyhow::bail!("no driver_id, driver_ref, code or number given"),
+    }
+}
+
+fn constructor_key(
+    constructor_id: Option<i32>,
+    constructor_ref: &Option<String>,
+) -> anyhow::Result<ConstructorKey> {
+    match (constructor_id, constructor_ref) {
+        (Some(id), _) => Ok(ConstructorKey::Id(id)),
+        (_, Some(constructor_ref)) => Ok(ConstructorKey::Ref(constructor_ref.clone())),
+        _ => anyhow::bail!("no constructor_id or constructor_ref given"),
+    }
+}
+
 fn de_time<'de, D>(de: D) -> Result<chrono::TimeDelta, D::Error>
 where
     D: Deserializer<'de>,
@@ -125,14 +291,96 @@ fn de_pit_stop_duration<'de, D>(de: D) -> Result<chrono::TimeDelta, D::Error>
 where
     D: Deserializer<'de>,
 {
+    let input = String::deserialize(de)?;
+    parse_pit_stop_duration(&input)
+        .ok_or_else(|| D::Error::custom(format!("invalid pit stop duration ({})", input)))
+}
+
+/// Parses a pit stop duration, `S.mmm` or `M:SS.mmm` for the long stops
+/// behind a red flag.
+fn parse_pit_stop_duration(input: &str) -> Option<chrono::TimeDelta> {
     // Trick we had hours and minutes because chrono needs it to parse a NaiveTime
-    let input = format!("00:00:{}", String::deserialize(de)?);
-    if let Ok(time) = chrono::NaiveTime::parse_from_str(&input, "%H:%M:%S%.3f") {
-        Ok(time.signed_duration_since(chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap()))
+    let input = if input.contains(':') {
+        format!("00:{}", input)
+    } else {
+        format!("00:00:{}", input)
+    };
+    let time = chrono::NaiveTime::parse_from_str(&input, "%H:%M:%S%.3f").ok()?;
+    Some(time.signed_duration_since(chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap()))
+}
+
+fn ser_time<S>(time: &chrono::TimeDelta, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(&format!(
+        "{}:{:02}.{:03}",
+        time.num_minutes(),
+        time.num_seconds() % 60,
+        time.num_milliseconds() % 1000
+    ))
+}
+
+/// Whole points are written without decimals, like in the input files.
+fn ser_points<S>(points: &f32, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if points.fract() == 0.0 {
+        ser.serialize_i64(*points as i64)
     } else {
-        Err(D::Error::custom(format!(
-            "invalid pit stop duration ({})",
-            input
-        )))
+        ser.serialize_f32(*points)
+    }
+}
+
+fn ser_local_time<S>(time: &chrono::NaiveTime, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(&time.format("%H:%M:%S").to_string())
+}
+
+fn ser_pit_stop_duration<S>(duration: &chrono::TimeDelta, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(&format_pit_stop_duration(duration))
+}
+
+/// Formats a pit stop duration like f1db does, `S.mmm` below a minute and
+/// `M:SS.mmm` above.
+pub fn format_pit_stop_duration(duration: &chrono::TimeDelta) -> String {
+    if duration.num_minutes() > 0 {
+        format!(
+            "{}:{:02}.{:03}",
+            duration.num_minutes(),
+            duration.num_seconds() % 60,
+            duration.num_milliseconds() % 1000
+        )
+    } else {
+        format!(
+            "{}.{:03}",
+            duration.num_seconds(),
+            duration.num_milliseconds() % 1000
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pit_stop_durations_round_trip() {
+        for (input, milliseconds) in [
+            ("2.345", 2_345),
+            ("22.045", 22_045),
+            ("16:44.718", 1_004_718),
+        ] {
+            let duration = parse_pit_stop_duration(input).unwrap();
+            assert_eq!(duration.num_milliseconds(), milliseconds);
+            assert_eq!(format_pit_stop_duration(&duration), input);
+        }
+        assert_eq!(parse_pit_stop_duration("22,045"), None);
     }
 }
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::checks::Finding;
+use crate::importers::Counts;
+
+/// Machine readable summary of an import, written with `--report` so the CI
+/// can publish it and gate on it.
+#[derive(Serialize, Debug)]
+pub struct Report {
+    pub version: &'static str,
+    pub rounds: Vec<Round>,
+    /// Whether the import succeeded and all its rounds were committed.
+    pub committed: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    #[serde(skip)]
+    started: Instant,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Round {
+    pub year: i32,
+    pub round: u16,
+    pub race_id: Option<i32>,
+    pub sprint: Option<bool>,
+    /// Id of the run in `updaterRuns`.
+    pub run_id: Option<i32>,
+    pub tables: BTreeMap<&'static str, Table>,
+    pub findings: Vec<Finding>,
+    pub committed: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Table {
+    #[serde(flatten)]
+    pub counts: Counts,
+    pub duration_ms: u64,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Report {
+            version: env!("CARGO_PKG_VERSION"),
+            rounds: Vec::new(),
+            committed: false,
+            error: None,
+            duration_ms: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Starts the report of a round, filled in as it is imported.
+    pub fn round(&mut self, year: i32, round: u16) -> &mut Round {
+        self.rounds.push(Round {
+            year,
+            round,
+            race_id: None,
+            sprint: None,
+            run_id: None,
+            tables: BTreeMap::new(),
+            findings: Vec::new(),
+            committed: false,
+            duration_ms: 0,
+        });
+        self.rounds.last_mut().expect("round just pushed")
+    }
+
+    /// Records the outcome of the import and writes the report to `path`, if
+    /// one was given.
+    pub fn save(mut self, path: Option<&Path>, result: &anyhow::Result<()>) -> anyhow::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+
+        self.committed = result.is_ok();
+        self.error = result.as_ref().err().map(|e| format!("{e:#}"));
+        self.duration_ms = millis(self.started.elapsed());
+
+        std::fs::write(path, serde_json::to_string_pretty(&self)?)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        log::info!("report written to {}", path.display());
+
+        Ok(())
+    }
+}
+
+pub fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeSet, HashMap};
+
+use sea_query::{Alias, DynIden, Expr, IntoIden, Query, SelectStatement, UnionType};
+
+use crate::backend::Transaction;
+use crate::constructor_names::ConstructorNames;
+use crate::driver_numbers::DriverNumbers;
+use crate::input::Input;
+use crate::models::{ConstructorKey, DriverKey};
+use crate::tables::{Constructors, Drivers, Qualifying, Races, Results};
+
+/// Resolves the natural keys found in the csv files to f1db surrogate ids.
+///
+/// The `drivers` and `constructors` tables are read once, on first use, and
+/// every resolved key is cached until [`Resolver::entries_changed`].
+pub struct Resolver<'a> {
+    race_id: i32,
+    year: i32,
+    input: &'a Input<'a>,
+    drivers: Option<DriverIndex>,
+    constructors: Option<ConstructorIndex>,
+    numbers: Option<DriverNumbers>,
+    names: Option<ConstructorNames>,
+    cache: HashMap<DriverKey, i32>,
+    constructor_cache: HashMap<ConstructorKey, i32>,
+}
+
+struct DriverIndex {
+    by_ref: HashMap<String, i32>,
+    by_code: HashMap<String, BTreeSet<i32>>,
+    season: BTreeSet<i32>,
+}
+
+struct ConstructorIndex {
+    by_ref: HashMap<String, i32>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(race_id: i32, year: i32, input: &'a Input<'a>) -> Self {
+        Self {
+            race_id,
+            year,
+            input,
+            drivers: None,
+            constructors: None,
+            numbers: None,
+            names: None,
+            cache: HashMap::new(),
+            constructor_cache: HashMap::new(),
+        }
+    }
+
+    pub fn driver_id(&mut self, key: &DriverKey, tx: &mut dyn Transaction) -> anyhow::Result<i32> {
+        if let DriverKey::Id(id) = key {
+            return Ok(*id);
+        }
+        if let Some(id) = self.cache.get(key) {
+            return Ok(*id);
+        }
+
+        let id = match key {
+            DriverKey::Id(id) => *id,
+            DriverKey::Ref(driver_ref) => *self
+                .drivers(tx)?
+                .by_ref
+                .get(driver_ref)
+                .ok_or_else(|| anyhow::anyhow!("unknown driver_ref ({driver_ref})"))?,
+            DriverKey::Code(code) => {
+                let year = self.year;
+                let drivers = self.drivers(tx)?;
+                let Some(candidates) = drivers.by_code.get(code) else {
+                    anyhow::bail!("unknown driver code ({code})");
+                };
+
+                // Codes get reused over the decades, prefer this season's driver
+                let season = candidates.intersection(&drivers.season).collect::<Vec<_>>();
+                match (&season[..], candidates.len()) {
+                    ([id], _) => **id,
+                    ([], 1) => *candidates.first().unwrap(),
+                    _ => anyhow::bail!(
+                        "driver code {code} is ambiguous in {year}, candidates: {candidates:?}"
+                    ),
+                }
+            }
+            DriverKey::Number(number) => {
+                let id = self.driver_id_by_number(*number, tx)?;
+                log::warn!(
+                    "car #{number} resolved to driver {id} by its number, \
+                     give a driver_id, driver_ref or code to be sure"
+                );
+                id
+            }
+        };
+
+        self.cache.insert(key.clone(), id);
+        Ok(id)
+    }
+
+    /// Resolves a car number, for the files only knowing the drivers by it
+    /// like the sprint results.
+    pub fn driver_id_by_number(
+        &mut self,
+        number: u16,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<i32> {
+        if self.numbers.is_none() {
+            self.numbers = Some(DriverNumbers::load(
+                self.race_id,
+                self.year,
+                self.input,
+                tx,
+            )?);
+        }
+        self.numbers.as_ref().unwrap().driver_id(number)
+    }
+
+    /// Forgets what was read from the entries of the season, to be called
+    /// once rows were written so the numbers and codes see the new entries.
+    pub fn entries_changed(&mut self) {
+        self.drivers = None;
+        self.numbers = None;
+        self.cache
+            .retain(|key, _| matches!(key, DriverKey::Id(_) | DriverKey::Ref(_)));
+    }
+
+    pub fn constructor_id(
+        &mut self,
+        key: &ConstructorKey,
+        tx: &mut dyn Transaction,
+    ) -> anyhow::Result<i32> {
+        if let ConstructorKey::Id(id) = key {
+            return Ok(*id);
+        }
+        if let Some(id) = self.constructor_cache.get(key) {
+            return Ok(*id);
+        }
+
+        let id = match key {
+            ConstructorKey::Id(id) => *id,
+            ConstructorKey::Ref(constructor_ref) => *self
+                .constructors(tx)?
+                .by_ref
+                .get(constructor_ref)
+                .ok_or_else(|| anyhow::anyhow!("unknown constructor_ref ({constructor_ref})"))?,
+            ConstructorKey::Name(name) => {
+                if self.names.is_none() {
+                    self.names = Some(ConstructorNames::load(self.year, self.input, tx)?);
+                }
+                self.names.as_ref().unwrap().constructor_id(name)?
+            }
+        };
+
+        self.constructor_cache.insert(key.clone(), id);
+        Ok(id)
+    }
+
+    fn drivers(&mut self, tx: &mut dyn Transaction) -> anyhow::Result<&DriverIndex> {
+        if self.drivers.is_none() {
+            let mut drivers = DriverIndex {
+                by_ref: HashMap::new(),
+                by_code: HashMap::new(),
+                season: BTreeSet::new(),
+            };
+
+            let query = Query::select()
+                .columns([Drivers::DriverID, Drivers::DriverRef, Drivers::Code])
+                .from(Drivers::Table)
+                .to_owned();
+            for row in tx.query(&query)? {
+                let id = row.get(0)?;
+                drivers.by_ref.insert(row.get(1)?, id);
+                if let Some(code) = row.get_opt(2)? {
+                    drivers.by_code.entry(code).or_default().insert(id);
+                }
+            }
+
+            drivers.season = tx
+                .query(&season_entries(self.year, &["driverId"]))?
+                .iter()
+                .map(|row| row.get(0))
+                .collect::<Result<_, _>>()?;
+
+            self.drivers = Some(drivers);
+        }
+
+        Ok(self.drivers.as_ref().unwrap())
+    }
+
+    fn constructors(&mut self, tx: &mut dyn Transaction) -> anyhow::Result<&ConstructorIndex> {
+        if self.constructors.is_none() {
+            let mut constructors = ConstructorIndex {
+                by_ref: HashMap::new(),
+            };
+
+            let query = Query::select()
+                .columns([Constructors::ConstructorID, Constructors::ConstructorRef])
+                .from(Constructors::Table)
+                .to_owned();
+            for row in tx.query(&query)? {
+                constructors.by_ref.insert(row.get(1)?, row.get(0)?);
+            }
+
+            self.constructors = Some(constructors);
+        }
+
+        Ok(self.constructors.as_ref().unwrap())
+    }
+}
+
+/// Selects the given columns of the results and qualifying rows of a season,
+/// both tables naming them the same way.
+pub fn season_entries(year: i32, columns: &[&str]) -> SelectStatement {
+    let entries = |table: DynIden| {
+        let mut query = Query::select();
+        for column in columns {
+            query.column((table.clone(), Alias::new(*column)));
+        }
+        query
+            .from(table.clone())
+            .inner_join(
+                Races::Table,
+                Expr::col((Races::Table, Races::RaceID)).equals((table, Alias::new("raceId"))),
+            )
+            .and_where(Expr::col((Races::Table, Races::Year)).eq(year))
+            .to_owned()
+    };
+
+    entries(Results::Table.into_iden())
+        .union(UnionType::Distinct, entries(Qualifying::Table.into_iden()))
+        .to_owned()
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn numbers_see_the_entries_written_by_the_import() {
+        let mut db = testing::database();
+        let mut tx = db.transaction().unwrap();
+        let folder = testing::folder();
+        let input = Input::new(&folder.0, None);
+        let mut resolver = Resolver::new(testing::RACE, 2026, &input);
+
+        // Only round 4 knows #4, Norris drove it
+        assert_eq!(resolver.driver_id_by_number(4, tx.as_mut()).unwrap(), 846);
+
+        tx.exec_sql(
+            "INSERT INTO qualifying (raceId, driverId, constructorId, number, position) \
+             VALUES (2, 830, 9, 4, 1)",
+        )
+        .unwrap();
+        resolver.entries_changed();
+
+        // The entries of the race come before the ones of the season
+        assert_eq!(resolver.driver_id_by_number(4, tx.as_mut()).unwrap(), 830);
+        assert_eq!(
+            resolver
+                .driver_id(&DriverKey::Number(4), tx.as_mut())
+                .unwrap(),
+            830
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use sea_query::{Expr, Func, Query};
+
+use crate::backend::Transaction;
+use crate::journal::Journal;
+use crate::tables::UpdaterRuns;
+
+/// Audit record of an import, written to `updaterRuns` in the transaction of
+/// the import so the published dump tells where its rows come from.
+#[derive(Debug)]
+pub struct Run {
+    pub race_id: i32,
+    pub round: u16,
+    pub year: i32,
+    pub sprint: bool,
+    /// Rows written per imported table.
+    pub row_counts: BTreeMap<&'static str, u64>,
+    /// Sha-256 of each input file read.
+    pub input_hashes: BTreeMap<&'static str, String>,
+    pub journal: Journal,
+}
+
+impl Run {
+    pub fn new(race_id: i32, round: u16, year: i32, sprint: bool) -> Self {
+        Self {
+            race_id,
+            round,
+            year,
+            sprint,
+            row_counts: BTreeMap::new(),
+            input_hashes: BTreeMap::new(),
+            journal: Journal::default(),
+        }
+    }
+
+    pub fn record(&self, tx: &mut dyn Transaction) -> anyhow::Result<i32> {
+        tx.exec(
+            Query::insert()
+                .into_table(UpdaterRuns::Table)
+                .columns([
+                    UpdaterRuns::RaceID,
+                    UpdaterRuns::Round,
+                    UpdaterRuns::Year,
+                    UpdaterRuns::Sprint,
+                    UpdaterRuns::RowCounts,
+                    UpdaterRuns::InputHashes,
+                    UpdaterRuns::Version,
+                    UpdaterRuns::CreatedAt,
+                ])
+                .values([
+                    self.race_id.into(),
+                    self.round.into(),
+                    self.year.into(),
+                    self.sprint.into(),
+                    serde_json::to_string(&self.row_counts)?.into(),
+                    serde_json::to_string(&self.input_hashes)?.into(),
+                    env!("CARGO_PKG_VERSION").into(),
+                    chrono::Utc::now()
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                        .into(),
+                ])?,
+        )?;
+
+        // Ids are not returned the same way by every database, the run is the
+        // latest one in this transaction anyway
+        let run_id = tx
+            .query(
+                &Query::select()
+                    .expr(Func::max(Expr::col(UpdaterRuns::RunID)))
+                    .from(UpdaterRuns::Table)
+                    .to_owned(),
+            )?
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("run not recorded"))?
+            .get(0)?;
+        self.journal.write(run_id, tx)?;
+
+        log::info!("run {run_id} recorded: {:?}", self.row_counts);
+        Ok(run_id)
+    }
+}
@@ -0,0 +1,680 @@
+use sea_query::{
+    Alias, ColumnDef, ColumnSpec, ColumnType, Expr, ForeignKey, ForeignKeyCreateStatement, Iden,
+    Index, IndexCreateStatement, Query, Table, TableCreateStatement, TableRef,
+};
+
+use crate::backend::{Backend, Dialect, Row, Transaction};
+use crate::migrations;
+use crate::tables::*;
+
+/// Creates the f1db tables the updater reads and writes, if missing, along
+/// with its own tables.
+///
+/// The tables are created by the migrations so the database ends up at the
+/// latest version, ready for an import, and later migrations apply on top.
+pub fn init(db: &mut dyn Backend) -> anyhow::Result<()> {
+    migrations::migrate(db, None)?;
+    log::info!("schema initialized");
+
+    Ok(())
+}
+
+pub fn create(
+    tx: &mut dyn Transaction,
+    statements: Vec<TableCreateStatement>,
+) -> anyhow::Result<()> {
+    for statement in statements {
+        tx.exec_schema(&statement)?;
+    }
+
+    Ok(())
+}
+
+/// Drops the tables of the statements, in reverse order.
+pub fn drop(tx: &mut dyn Transaction, statements: Vec<TableCreateStatement>) -> anyhow::Result<()> {
+    for statement in statements.iter().rev() {
+        if let Some(table) = statement.get_table_name() {
+            tx.exec_schema(Table::drop().table(table.clone()).if_exists())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Compares the live database with the tables this binary was built for.
+///
+/// Every column must exist with a compatible type and nullability, a column
+/// left empty by the updater may only be `NOT NULL` if it has a default.
+pub fn check(tx: &mut dyn Transaction) -> anyhow::Result<()> {
+    let mut diff = Vec::new();
+
+    for statement in tables() {
+        let Some(TableRef::Table(table)) = statement.get_table_name() else {
+            continue;
+        };
+        let table = table.to_string();
+        let live = live_columns(tx, &table)?;
+        if live.is_empty() {
+            diff.push(format!("{table}: missing table"));
+            continue;
+        }
+
+        for column in statement.get_columns() {
+            let name = column.get_column_name();
+            let Some(found) = live.iter().find(|c| c.name == name) else {
+                diff.push(format!("{table}.{name}: missing column"));
+                continue;
+            };
+
+            let expected = column.get_column_type().map_or("other", type_family);
+            let expected = match (tx.dialect(), expected) {
+                #[cfg(feature = "sqlite")]
+                (Dialect::Sqlite, "date" | "time" | "datetime") => "text",
+                (_, family) => family,
+            };
+            let actual = live_family(&found.sql_type);
+            if expected != actual {
+                diff.push(format!(
+                    "{table}.{name}: expected {expected}, found {}",
+                    found.sql_type
+                ));
+            }
+
+            let not_null = column
+                .get_column_spec()
+                .iter()
+                .any(|spec| matches!(spec, ColumnSpec::NotNull | ColumnSpec::PrimaryKey));
+            if not_null && found.nullable {
+                diff.push(format!("{table}.{name}: expected NOT NULL, found nullable"));
+            } else if !not_null && !found.nullable && !found.has_default {
+                diff.push(format!(
+                    "{table}.{name}: expected nullable, found NOT NULL without default"
+                ));
+            }
+        }
+    }
+
+    let version = migrations::version(tx)?;
+    if version != migrations::LATEST {
+        diff.push(format!(
+            "migrations: database at version {version}, expected {} \
+             (run `f1-sql-updater migrate`)",
+            migrations::LATEST
+        ));
+    }
+
+    if !diff.is_empty() {
+        anyhow::bail!(
+            "the database schema does not match the one f1-sql-updater {} was built for:\n  {}",
+            env!("CARGO_PKG_VERSION"),
+            diff.join("\n  ")
+        );
+    }
+
+    log::info!("database schema checked");
+    Ok(())
+}
+
+pub fn table_exists(tx: &mut dyn Transaction, table: &str) -> anyhow::Result<bool> {
+    Ok(!live_columns(tx, table)?.is_empty())
+}
+
+pub fn column_exists(tx: &mut dyn Transaction, table: &str, column: &str) -> anyhow::Result<bool> {
+    Ok(live_columns(tx, table)?.iter().any(|c| c.name == column))
+}
+
+struct LiveColumn {
+    name: String,
+    sql_type: String,
+    nullable: bool,
+    has_default: bool,
+}
+
+fn live_columns(tx: &mut dyn Transaction, table: &str) -> anyhow::Result<Vec<LiveColumn>> {
+    let current_schema = match tx.dialect() {
+        Dialect::Mysql => "DATABASE()",
+        #[cfg(feature = "postgres")]
+        Dialect::Postgres => "current_schema()",
+        // No information_schema in SQLite, the pragma gives the same columns
+        #[cfg(feature = "sqlite")]
+        Dialect::Sqlite => {
+            let rows = tx.query_sql(&format!(
+                "SELECT name, type, CASE WHEN \"notnull\" OR pk > 0 THEN 'NO' ELSE 'YES' END, \
+                 dflt_value FROM pragma_table_info('{table}')"
+            ))?;
+            return rows.iter().map(live_column).collect();
+        }
+    };
+
+    let rows = tx.query(
+        &Query::select()
+            .columns(["column_name", "data_type", "is_nullable", "column_default"].map(Alias::new))
+            .from((Alias::new("information_schema"), Alias::new("columns")))
+            .and_where(Expr::col(Alias::new("table_schema")).eq(Expr::cust(current_schema)))
+            .and_where(Expr::col(Alias::new("table_name")).eq(table))
+            .to_owned(),
+    )?;
+    rows.iter().map(live_column).collect()
+}
+
+fn live_column(row: &Row) -> anyhow::Result<LiveColumn> {
+    Ok(LiveColumn {
+        name: row.get(0)?,
+        sql_type: row.get::<String>(1)?.to_lowercase(),
+        nullable: row.get::<String>(2)? == "YES",
+        has_default: row.get_opt::<String>(3)?.is_some(),
+    })
+}
+
+fn type_family(column_type: &ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::Integer | ColumnType::BigInteger | ColumnType::SmallInteger => "integer",
+        ColumnType::String(_) | ColumnType::Char(_) | ColumnType::Text => "text",
+        ColumnType::Float | ColumnType::Double | ColumnType::Decimal(_) => "float",
+        ColumnType::Boolean => "boolean",
+        ColumnType::DateTime => "datetime",
+        ColumnType::Date => "date",
+        ColumnType::Time => "time",
+        _ => "other",
+    }
+}
+
+/// Groups the type names of the databases, `varchar(255)` and
+/// `character varying` are both text.
+fn live_family(sql_type: &str) -> &'static str {
+    if sql_type.contains("bool") || sql_type == "tinyint" {
+        "boolean"
+    } else if sql_type.starts_with("datetime") || sql_type.starts_with("timestamp") {
+        "datetime"
+    } else if sql_type.starts_with("date") {
+        "date"
+    } else if sql_type.starts_with("time") {
+        "time"
+    } else if sql_type.contains("int") {
+        "integer"
+    } else if ["char", "text", "clob"]
+        .iter()
+        .any(|t| sql_type.contains(t))
+    {
+        "text"
+    } else if ["real", "float", "double", "decimal", "numeric"]
+        .iter()
+        .any(|t| sql_type.contains(t))
+    {
+        "float"
+    } else {
+        "other"
+    }
+}
+
+/// The `CREATE TABLE` statements, referenced tables first.
+///
+/// Types and keys follow the f1db MySQL dump, with a few unique keys on top so
+/// duplicate rows are refused by every backend.
+pub fn tables() -> Vec<TableCreateStatement> {
+    vec![
+        Table::create()
+            .table(Circuits::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Circuits::CircuitID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(Circuits::CircuitRef)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(ColumnDef::new(Circuits::Name).string_len(255).not_null())
+            .col(ColumnDef::new(Circuits::Location).string_len(255))
+            .col(ColumnDef::new(Circuits::Country).string_len(255))
+            .col(ColumnDef::new(Circuits::Lat).float())
+            .col(ColumnDef::new(Circuits::Lng).float())
+            .col(ColumnDef::new(Circuits::Alt).integer())
+            .col(ColumnDef::new(Circuits::Url).string_len(255).not_null())
+            .index(&mut unique("circuits_ref", [Circuits::CircuitRef]))
+            .to_owned(),
+        Table::create()
+            .table(Constructors::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Constructors::ConstructorID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(Constructors::ConstructorRef)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(Constructors::Name)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(ColumnDef::new(Constructors::Nationality).string_len(255))
+            .col(ColumnDef::new(Constructors::Url).string_len(255).not_null())
+            .index(&mut unique(
+                "constructors_ref",
+                [Constructors::ConstructorRef],
+            ))
+            .to_owned(),
+        Table::create()
+            .table(Drivers::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Drivers::DriverID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(Drivers::DriverRef)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(ColumnDef::new(Drivers::Number).integer())
+            .col(ColumnDef::new(Drivers::Code).string_len(3))
+            .col(ColumnDef::new(Drivers::Forename).string_len(255).not_null())
+            .col(ColumnDef::new(Drivers::Surname).string_len(255).not_null())
+            .col(ColumnDef::new(Drivers::Dob).date())
+            .col(ColumnDef::new(Drivers::Nationality).string_len(255))
+            .col(ColumnDef::new(Drivers::Url).string_len(255).not_null())
+            .index(&mut unique("drivers_ref", [Drivers::DriverRef]))
+            .to_owned(),
+        Table::create()
+            .table(Status::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Status::StatusID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Status::Status).string_len(255).not_null())
+            .to_owned(),
+        Table::create()
+            .table(Races::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Races::RaceID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Races::Year).integer().not_null())
+            .col(ColumnDef::new(Races::Round).integer().not_null())
+            .col(ColumnDef::new(Races::CircuitID).integer().not_null())
+            .col(ColumnDef::new(Races::Name).string_len(255).not_null())
+            .col(ColumnDef::new(Races::Date).date().not_null())
+            .col(ColumnDef::new(Races::Time).time())
+            .col(ColumnDef::new(Races::Url).string_len(255))
+            .col(ColumnDef::new(Races::Fp1Date).date())
+            .col(ColumnDef::new(Races::Fp1Time).time())
+            .col(ColumnDef::new(Races::Fp2Date).date())
+            .col(ColumnDef::new(Races::Fp2Time).time())
+            .col(ColumnDef::new(Races::Fp3Date).date())
+            .col(ColumnDef::new(Races::Fp3Time).time())
+            .col(ColumnDef::new(Races::QualiDate).date())
+            .col(ColumnDef::new(Races::QualiTime).time())
+            .col(ColumnDef::new(Races::SprintDate).date())
+            .col(ColumnDef::new(Races::SprintTime).time())
+            .col(ColumnDef::new(Races::ScheduledLaps).integer())
+            .col(ColumnDef::new(Races::CompletedLaps).integer())
+            .col(ColumnDef::new(Races::RedFlagged).boolean())
+            .col(ColumnDef::new(Races::Shortened).boolean())
+            .index(&mut unique("races_round", [Races::Year, Races::Round]))
+            .foreign_key(
+                ForeignKey::create()
+                    .from(Races::Table, Races::CircuitID)
+                    .to(Circuits::Table, Circuits::CircuitID),
+            )
+            .to_owned(),
+        Table::create()
+            .table(LapTimes::Table)
+            .if_not_exists()
+            .col(ColumnDef::new(LapTimes::RaceID).integer().not_null())
+            .col(ColumnDef::new(LapTimes::DriverID).integer().not_null())
+            .col(ColumnDef::new(LapTimes::Lap).integer().not_null())
+            .col(ColumnDef::new(LapTimes::Position).integer())
+            .col(ColumnDef::new(LapTimes::Time).string_len(255))
+            .col(ColumnDef::new(LapTimes::Milliseconds).integer())
+            .primary_key(
+                Index::create()
+                    .col(LapTimes::RaceID)
+                    .col(LapTimes::DriverID)
+                    .col(LapTimes::Lap),
+            )
+            .foreign_key(&mut race(LapTimes::Table, LapTimes::RaceID))
+            .foreign_key(&mut driver(LapTimes::Table, LapTimes::DriverID))
+            .to_owned(),
+        Table::create()
+            .table(PitStops::Table)
+            .if_not_exists()
+            .col(ColumnDef::new(PitStops::RaceID).integer().not_null())
+            .col(ColumnDef::new(PitStops::DriverID).integer().not_null())
+            .col(ColumnDef::new(PitStops::Stop).integer().not_null())
+            .col(ColumnDef::new(PitStops::Lap).integer().not_null())
+            .col(ColumnDef::new(PitStops::Time).time().not_null())
+            .col(ColumnDef::new(PitStops::Duration).string_len(255))
+            .col(ColumnDef::new(PitStops::Milliseconds).integer())
+            .primary_key(
+                Index::create()
+                    .col(PitStops::RaceID)
+                    .col(PitStops::DriverID)
+                    .col(PitStops::Stop),
+            )
+            .foreign_key(&mut race(PitStops::Table, PitStops::RaceID))
+            .foreign_key(&mut driver(PitStops::Table, PitStops::DriverID))
+            .to_owned(),
+        Table::create()
+            .table(Qualifying::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Qualifying::QualifyID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Qualifying::RaceID).integer().not_null())
+            .col(ColumnDef::new(Qualifying::DriverID).integer().not_null())
+            .col(
+                ColumnDef::new(Qualifying::ConstructorID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(Qualifying::Number).integer().not_null())
+            .col(ColumnDef::new(Qualifying::Position).integer())
+            .col(ColumnDef::new(Qualifying::Q1).string_len(255))
+            .col(ColumnDef::new(Qualifying::Q2).string_len(255))
+            .col(ColumnDef::new(Qualifying::Q3).string_len(255))
+            .index(&mut unique(
+                "qualifying_driver",
+                [Qualifying::RaceID, Qualifying::DriverID],
+            ))
+            .foreign_key(&mut race(Qualifying::Table, Qualifying::RaceID))
+            .foreign_key(&mut driver(Qualifying::Table, Qualifying::DriverID))
+            .foreign_key(&mut constructor(
+                Qualifying::Table,
+                Qualifying::ConstructorID,
+            ))
+            .to_owned(),
+        // No unique key on the results, drivers shared cars in the 50s
+        Table::create()
+            .table(Results::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Results::ResultID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Results::RaceID).integer().not_null())
+            .col(ColumnDef::new(Results::DriverID).integer().not_null())
+            .col(ColumnDef::new(Results::ConstructorID).integer().not_null())
+            .col(ColumnDef::new(Results::Number).integer())
+            .col(ColumnDef::new(Results::Grid).integer().not_null())
+            .col(ColumnDef::new(Results::Position).integer())
+            .col(
+                ColumnDef::new(Results::PositionText)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(ColumnDef::new(Results::PositionOrder).integer().not_null())
+            .col(ColumnDef::new(Results::Points).float().not_null())
+            .col(ColumnDef::new(Results::Laps).integer().not_null())
+            .col(ColumnDef::new(Results::Time).string_len(255))
+            .col(ColumnDef::new(Results::Milliseconds).integer())
+            .col(ColumnDef::new(Results::FastestLap).integer())
+            .col(ColumnDef::new(Results::Rank).integer())
+            .col(ColumnDef::new(Results::FastestLapTime).string_len(255))
+            .col(ColumnDef::new(Results::FastestLapSpeed).string_len(255))
+            .col(ColumnDef::new(Results::StatusID).integer())
+            .foreign_key(&mut race(Results::Table, Results::RaceID))
+            .foreign_key(&mut driver(Results::Table, Results::DriverID))
+            .foreign_key(&mut constructor(Results::Table, Results::ConstructorID))
+            .foreign_key(&mut status(Results::Table, Results::StatusID))
+            .to_owned(),
+        Table::create()
+            .table(SprintResults::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(SprintResults::SprintResultID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(SprintResults::RaceID).integer().not_null())
+            .col(ColumnDef::new(SprintResults::DriverID).integer().not_null())
+            .col(
+                ColumnDef::new(SprintResults::ConstructorID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(SprintResults::Number).integer().not_null())
+            .col(ColumnDef::new(SprintResults::Grid).integer().not_null())
+            .col(ColumnDef::new(SprintResults::Position).integer())
+            .col(
+                ColumnDef::new(SprintResults::PositionText)
+                    .string_len(255)
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(SprintResults::PositionOrder)
+                    .integer()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(SprintResults::Points).float().not_null())
+            .col(ColumnDef::new(SprintResults::Laps).integer().not_null())
+            .col(ColumnDef::new(SprintResults::Time).string_len(255))
+            .col(ColumnDef::new(SprintResults::Milliseconds).integer())
+            .col(ColumnDef::new(SprintResults::FastestLap).integer())
+            .col(ColumnDef::new(SprintResults::FastestLapTime).string_len(255))
+            .col(ColumnDef::new(SprintResults::FastestLapSpeed).string_len(255))
+            .col(ColumnDef::new(SprintResults::StatusID).integer())
+            .index(&mut unique(
+                "sprint_results_driver",
+                [SprintResults::RaceID, SprintResults::DriverID],
+            ))
+            .foreign_key(&mut race(SprintResults::Table, SprintResults::RaceID))
+            .foreign_key(&mut driver(SprintResults::Table, SprintResults::DriverID))
+            .foreign_key(&mut constructor(
+                SprintResults::Table,
+                SprintResults::ConstructorID,
+            ))
+            .foreign_key(&mut status(SprintResults::Table, SprintResults::StatusID))
+            .to_owned(),
+        Table::create()
+            .table(DriverStandings::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(DriverStandings::DriverStandingsID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(DriverStandings::RaceID).integer().not_null())
+            .col(
+                ColumnDef::new(DriverStandings::DriverID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(DriverStandings::Points).float().not_null())
+            .col(ColumnDef::new(DriverStandings::Position).integer())
+            .col(ColumnDef::new(DriverStandings::PositionText).string_len(255))
+            .col(ColumnDef::new(DriverStandings::Wins).integer().not_null())
+            .index(&mut unique(
+                "driver_standings_driver",
+                [DriverStandings::RaceID, DriverStandings::DriverID],
+            ))
+            .foreign_key(&mut race(DriverStandings::Table, DriverStandings::RaceID))
+            .foreign_key(&mut driver(
+                DriverStandings::Table,
+                DriverStandings::DriverID,
+            ))
+            .to_owned(),
+        Table::create()
+            .table(ConstructorStandings::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(ConstructorStandings::ConstructorStandingsID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(ConstructorStandings::RaceID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(ConstructorStandings::ConstructorID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(ConstructorStandings::Points)
+                    .float()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(ConstructorStandings::Position).integer())
+            .col(ColumnDef::new(ConstructorStandings::PositionText).string_len(255))
+            .col(
+                ColumnDef::new(ConstructorStandings::Wins)
+                    .integer()
+                    .not_null(),
+            )
+            .index(&mut unique(
+                "constructor_standings_constructor",
+                [
+                    ConstructorStandings::RaceID,
+                    ConstructorStandings::ConstructorID,
+                ],
+            ))
+            .foreign_key(&mut race(
+                ConstructorStandings::Table,
+                ConstructorStandings::RaceID,
+            ))
+            .foreign_key(&mut constructor(
+                ConstructorStandings::Table,
+                ConstructorStandings::ConstructorID,
+            ))
+            .to_owned(),
+        Table::create()
+            .table(ConstructorResults::Table)
+            .if_not_exists()
+            .col(
+                ColumnDef::new(ConstructorResults::ConstructorResultsID)
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(ConstructorResults::RaceID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(ConstructorResults::ConstructorID)
+                    .integer()
+                    .not_null(),
+            )
+            .col(ColumnDef::new(ConstructorResults::Points).float())
+            .col(ColumnDef::new(ConstructorResults::Status).string_len(255))
+            .index(&mut unique(
+                "constructor_results_constructor",
+                [
+                    ConstructorResults::RaceID,
+                    ConstructorResults::ConstructorID,
+                ],
+            ))
+            .foreign_key(&mut race(
+                ConstructorResults::Table,
+                ConstructorResults::RaceID,
+            ))
+            .foreign_key(&mut constructor(
+                ConstructorResults::Table,
+                ConstructorResults::ConstructorID,
+            ))
+            .to_owned(),
+    ]
+}
+
+fn unique<T: Iden + 'static, const N: usize>(name: &str, columns: [T; N]) -> IndexCreateStatement {
+    let mut index = Index::create().name(name).unique().to_owned();
+    for column in columns {
+        index.col(column);
+    }
+    index
+}
+
+pub fn race<T: Iden + 'static, C: Iden + 'static>(
+    table: T,
+    column: C,
+) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .from(table, column)
+        .to(Races::Table, Races::RaceID)
+        .to_owned()
+}
+
+fn driver<T: Iden + 'static, C: Iden + 'static>(table: T, column: C) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .from(table, column)
+        .to(Drivers::Table, Drivers::DriverID)
+        .to_owned()
+}
+
+fn constructor<T: Iden + 'static, C: Iden + 'static>(
+    table: T,
+    column: C,
+) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .from(table, column)
+        .to(Constructors::Table, Constructors::ConstructorID)
+        .to_owned()
+}
+
+fn status<T: Iden + 'static, C: Iden + 'static>(table: T, column: C) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .from(table, column)
+        .to(Status::Table, Status::StatusID)
+        .to_owned()
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::backend;
+
+    #[test]
+    fn init_prepares_a_database_for_the_import() {
+        let mut db = backend::memory().unwrap();
+        init(db.as_mut()).unwrap();
+        // Running it again changes nothing
+        init(db.as_mut()).unwrap();
+
+        let mut tx = db.transaction().unwrap();
+        check(tx.as_mut()).unwrap();
+        assert_eq!(
+            migrations::version(tx.as_mut()).unwrap(),
+            migrations::LATEST
+        );
+        assert!(table_exists(tx.as_mut(), "updaterRuns").unwrap());
+        assert!(table_exists(tx.as_mut(), "updaterJournal").unwrap());
+    }
+}
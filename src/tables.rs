@@ -1,9 +1,30 @@
+// Variants mirror the f1db column names, `constructorResultsId` included
+#![allow(clippy::enum_variant_names)]
+
 use sea_query::Iden;
 
+#[derive(Iden)]
+pub enum Circuits {
+    Table,
+    #[iden = "circuitId"]
+    CircuitID,
+    #[iden = "circuitRef"]
+    CircuitRef,
+    Name,
+    Location,
+    Country,
+    Lat,
+    Lng,
+    Alt,
+    Url,
+}
+
 #[derive(Iden)]
 pub enum ConstructorResults {
     #[iden = "constructorResults"]
     Table,
+    #[iden = "constructorResultsId"]
+    ConstructorResultsID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "constructorId"]
@@ -16,6 +37,8 @@ pub enum ConstructorResults {
 pub enum ConstructorStandings {
     #[iden = "constructorStandings"]
     Table,
+    #[iden = "constructorStandingsId"]
+    ConstructorStandingsID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "constructorId"]
@@ -27,10 +50,24 @@ pub enum ConstructorStandings {
     Wins,
 }
 
+#[derive(Iden)]
+pub enum Constructors {
+    Table,
+    #[iden = "constructorId"]
+    ConstructorID,
+    #[iden = "constructorRef"]
+    ConstructorRef,
+    Name,
+    Nationality,
+    Url,
+}
+
 #[derive(Iden)]
 pub enum DriverStandings {
     #[iden = "driverStandings"]
     Table,
+    #[iden = "driverStandingsId"]
+    DriverStandingsID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "driverId"]
@@ -42,6 +79,22 @@ pub enum DriverStandings {
     Wins,
 }
 
+#[derive(Iden)]
+pub enum Drivers {
+    Table,
+    #[iden = "driverId"]
+    DriverID,
+    #[iden = "driverRef"]
+    DriverRef,
+    Number,
+    Code,
+    Forename,
+    Surname,
+    Dob,
+    Nationality,
+    Url,
+}
+
 #[derive(Iden)]
 pub enum LapTimes {
     #[iden = "lapTimes"]
@@ -74,6 +127,8 @@ pub enum PitStops {
 #[derive(Iden)]
 pub enum Qualifying {
     Table,
+    #[iden = "qualifyId"]
+    QualifyID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "driverId"]
@@ -87,9 +142,53 @@ pub enum Qualifying {
     Q3,
 }
 
+#[derive(Iden)]
+pub enum Races {
+    Table,
+    #[iden = "raceId"]
+    RaceID,
+    Year,
+    Round,
+    #[iden = "circuitId"]
+    CircuitID,
+    Name,
+    Date,
+    Time,
+    Url,
+    #[iden = "fp1_date"]
+    Fp1Date,
+    #[iden = "fp1_time"]
+    Fp1Time,
+    #[iden = "fp2_date"]
+    Fp2Date,
+    #[iden = "fp2_time"]
+    Fp2Time,
+    #[iden = "fp3_date"]
+    Fp3Date,
+    #[iden = "fp3_time"]
+    Fp3Time,
+    #[iden = "quali_date"]
+    QualiDate,
+    #[iden = "quali_time"]
+    QualiTime,
+    #[iden = "sprint_date"]
+    SprintDate,
+    #[iden = "sprint_time"]
+    SprintTime,
+    #[iden = "scheduledLaps"]
+    ScheduledLaps,
+    #[iden = "completedLaps"]
+    CompletedLaps,
+    #[iden = "redFlagged"]
+    RedFlagged,
+    Shortened,
+}
+
 #[derive(Iden)]
 pub enum Results {
     Table,
+    #[iden = "resultId"]
+    ResultID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "driverId"]
@@ -122,6 +221,8 @@ pub enum Results {
 pub enum SprintResults {
     #[iden = "sprintResults"]
     Table,
+    #[iden = "sprintResultId"]
+    SprintResultID,
     #[iden = "raceId"]
     RaceID,
     #[iden = "driverId"]
@@ -148,3 +249,59 @@ pub enum SprintResults {
     #[iden = "statusId"]
     StatusID,
 }
+
+#[derive(Iden)]
+pub enum Status {
+    Table,
+    #[iden = "statusId"]
+    StatusID,
+    Status,
+}
+
+#[derive(Iden)]
+pub enum UpdaterMigrations {
+    #[iden = "updaterMigrations"]
+    Table,
+    Version,
+    Name,
+    #[iden = "appliedAt"]
+    AppliedAt,
+}
+
+#[derive(Iden)]
+pub enum UpdaterRuns {
+    #[iden = "updaterRuns"]
+    Table,
+    #[iden = "runId"]
+    RunID,
+    #[iden = "raceId"]
+    RaceID,
+    Round,
+    Year,
+    Sprint,
+    #[iden = "rowCounts"]
+    RowCounts,
+    #[iden = "inputHashes"]
+    InputHashes,
+    Version,
+    #[iden = "createdAt"]
+    CreatedAt,
+    #[iden = "rolledBackAt"]
+    RolledBackAt,
+}
+
+#[derive(Iden)]
+pub enum UpdaterJournal {
+    #[iden = "updaterJournal"]
+    Table,
+    #[iden = "entryId"]
+    EntryID,
+    #[iden = "runId"]
+    RunID,
+    #[iden = "tableName"]
+    TableName,
+    #[iden = "rowKey"]
+    RowKey,
+    #[iden = "oldValues"]
+    OldValues,
+}
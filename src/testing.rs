@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "sqlite")]
+use crate::backend::{self, Backend, Transaction};
+#[cfg(feature = "sqlite")]
+use crate::checks::Strictness;
+#[cfg(feature = "sqlite")]
+use crate::cli::ImportOptions;
+#[cfg(feature = "sqlite")]
+use crate::{migrations, report};
+
+/// Race of round 5 of 2026, a sprint weekend whose input files are written by
+/// [`round_files`].
+#[cfg(feature = "sqlite")]
+pub const RACE: i32 = 2;
+
+/// An in-memory database at the latest version, holding round 4 of 2026 and
+/// the entries of the season.
+#[cfg(feature = "sqlite")]
+pub fn database() -> Box<dyn Backend> {
+    let mut db = backend::memory().unwrap();
+    migrations::migrate(db.as_mut(), None).unwrap();
+
+    let mut tx = db.transaction().unwrap();
+    for sql in [
+        "INSERT INTO circuits (circuitId, circuitRef, name, url) VALUES (1, 'miami', 'Miami', 'u')",
+        "INSERT INTO races (raceId, year, round, circuitId, name, date, url) VALUES \
+         (1, 2026, 4, 1, 'Miami Grand Prix', '2026-05-03', 'u'), \
+         (2, 2026, 5, 1, 'Emilia Romagna Grand Prix', '2026-05-17', 'u')",
+        "UPDATE races SET sprint_date = '2026-05-16' WHERE raceId = 2",
+        "INSERT INTO drivers (driverId, driverRef, number, code, forename, surname, url) VALUES \
+         (830, 'max_verstappen', 33, 'VER', 'Max', 'Verstappen', 'u'), \
+         (846, 'norris', 4, 'NOR', 'Lando', 'Norris', 'u')",
+        "INSERT INTO constructors (constructorId, constructorRef, name, url) VALUES \
+         (1, 'mclaren', 'McLaren', 'u'), (9, 'red_bull', 'Red Bull', 'u')",
+        "INSERT INTO qualifying (raceId, driverId, constructorId, number, position, q1) VALUES \
+         (1, 830, 9, 1, 1, '1:29.000'), (1, 846, 1, 4, 2, '1:29.100')",
+        "INSERT INTO results (raceId, driverId, constructorId, number, grid, position, \
+         positionText, positionOrder, points, laps) VALUES \
+         (1, 830, 9, 1, 1, 1, '1', 1, 25, 3), (1, 846, 1, 4, 2, 2, '2', 2, 18, 3)",
+        "INSERT INTO driverStandings (raceId, driverId, points, position, positionText, wins) \
+         VALUES (1, 830, 25, 1, '1', 1), (1, 846, 18, 2, '2', 0)",
+        "INSERT INTO constructorStandings (raceId, constructorId, points, position, \
+         positionText, wins) VALUES (1, 9, 25, 1, '1', 1), (1, 1, 18, 2, '2', 0)",
+    ] {
+        tx.exec_sql(sql).unwrap();
+    }
+    tx.commit().unwrap();
+
+    db
+}
+
+/// A temporary folder, removed when dropped.
+pub struct Folder(pub PathBuf);
+
+impl Drop for Folder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn folder() -> Folder {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let path = std::env::temp_dir().join(format!(
+        "f1-sql-updater-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&path).unwrap();
+    Folder(path)
+}
+
+/// The csv files of round 5, consistent with round 4 so every check passes.
+#[cfg(feature = "sqlite")]
+pub fn round_files() -> Folder {
+    let folder = folder();
+    for (name, content) in [
+        (
+            "lap_times",
+            "driver_ref,lap,position,time\n\
+             max_verstappen,1,1,1:30.000\n\
+             max_verstappen,2,1,1:30.000\n\
+             max_verstappen,3,1,1:30.000\n\
+             norris,1,2,1:30.500\n\
+             norris,2,2,1:30.500\n\
+             norris,3,2,1:30.500\n",
+        ),
+        (
+            "qualifying",
+            "code,constructor_ref,position,number,q1,q2,q3\n\
+             VER,red_bull,1,1,1:29.000,1:28.500,1:28.000\n\
+             NOR,mclaren,2,4,1:29.100,,\n",
+        ),
+        (
+            "results",
+            "driver_ref,constructor_ref,driver_number,position,grid,position_text,\
+             position_order,points,laps,time,milliseconds,fastest_lap,fatest_lap_time,rank,\
+             fastest_lap_speed\n\
+             max_verstappen,red_bull,1,1,1,1,1,25,3,4:30.000,270000,1,1:30.000,1,210.383\n\
+             norris,mclaren,4,2,2,2,2,18,3,+1.500,271500,,,,\n",
+        ),
+        (
+            "driver_standings",
+            "driver_ref,points,position,position_text,wins\n\
+             max_verstappen,58,1,1,2\n\
+             norris,43,2,2,0\n",
+        ),
+        (
+            "constructor_standings",
+            "constructor_ref,points,position,position_text,wins\n\
+             red_bull,58,1,1,2\n\
+             mclaren,43,2,2,0\n",
+        ),
+        (
+            "constructor_results",
+            "constructor_ref,points\n\
+             red_bull,25\n\
+             mclaren,18\n",
+        ),
+        (
+            "sprint_results",
+            "no,entrant,grid,position,positionOrder,points,laps,time,milliseconds,fastestLap,\
+             fastestLapTime,fastestLapSpeed\n\
+             1,Oracle Red Bull Racing,1,1,1,8,19,30:00.000,1800000,5,1:30.000,\n\
+             4,McLaren Formula 1 Team,2,2,2,7,19,+1.0,1801000,,,\n",
+        ),
+    ] {
+        std::fs::write(folder.0.join(format!("{name}.csv")), content).unwrap();
+    }
+
+    folder
+}
+
+/// The options of a plain `f1-sql-updater <round>`.
+#[cfg(feature = "sqlite")]
+pub fn options() -> ImportOptions {
+    ImportOptions {
+        format: None,
+        only: Vec::new(),
+        skip: Vec::new(),
+        no_schema_check: false,
+        no_checks: false,
+        strictness: Strictness::Normal,
+        check_slow_laps: false,
+        report: None,
+    }
+}
+
+/// Imports round 5 from `files` as a sprint weekend.
+#[cfg(feature = "sqlite")]
+pub fn import(files: &Folder, tx: &mut dyn Transaction) -> anyhow::Result<report::Round> {
+    let mut report = report::Report::new();
+    let round = report.round(2026, 5);
+    crate::import_round(&options(), &files.0, 2026, 5, None, round, tx)?;
+
+    Ok(report.rounds.remove(0))
+}